@@ -1,26 +1,39 @@
 use axum::{
-    extract::{Path, State},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
     http::StatusCode,
-    response::{Html, Json},
-    routing::{get, post, delete},
+    response::{Html, Json, Response},
+    routing::{get, post, delete, patch},
     Router,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, RwLock};
 use std::fs;
 use std::path::PathBuf;
+use tokio::sync::broadcast;
 use tower_http::cors::CorsLayer;
 use tracing::{info, warn, error};
 use uuid::Uuid;
 
+/// Free-form node/edge metadata. Values are arbitrary JSON; files written when
+/// metadata was string-only load unchanged, with each value read as a JSON string.
+pub type Metadata = HashMap<String, serde_json::Value>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
     pub id: String,
     pub label: String,
     pub color: Option<String>,
     pub size: Option<f64>,
-    pub metadata: HashMap<String, String>,
+    pub metadata: Metadata,
+    /// Server-computed position, absent until a layout has been run.
+    #[serde(default)]
+    pub x: Option<f64>,
+    #[serde(default)]
+    pub y: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,13 +44,75 @@ pub struct Edge {
     pub label: Option<String>,
     pub weight: Option<f64>,
     pub color: Option<String>,
-    pub metadata: HashMap<String, String>,
+    pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Graph {
     pub nodes: HashMap<String, Node>,
     pub edges: HashMap<String, Edge>,
+    /// When false, edges are traversable in both directions. Files written
+    /// before this field existed load as directed.
+    #[serde(default = "default_directed")]
+    pub directed: bool,
+}
+
+fn default_directed() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutAlgorithm {
+    Force,
+    Circular,
+}
+
+const LAYOUT_RADIUS: f64 = 300.0;
+const FORCE_ITERATIONS: usize = 100;
+/// Upper bound for client-requested force iterations. Each iteration is
+/// O(nodes²) and runs under the graph write lock.
+const MAX_LAYOUT_ITERATIONS: usize = 1000;
+
+/// Quotes a string as a DOT identifier, escaping quotes, backslashes and
+/// line breaks.
+fn dot_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// 64-bit FNV-1a: a stable hash (unlike `DefaultHasher`) for per-node
+/// layout seeds and project file names.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Small deterministic PRNG (splitmix64) so layouts are reproducible without
+/// pulling in a rand dependency.
+struct LayoutRng(u64);
+
+impl LayoutRng {
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
 }
 
 impl Graph {
@@ -45,6 +120,7 @@ impl Graph {
         Self {
             nodes: HashMap::new(),
             edges: HashMap::new(),
+            directed: true,
         }
     }
     
@@ -114,17 +190,81 @@ impl Graph {
         Ok(())
     }
 
-    fn remove_node(&mut self, node_id: &str) -> Result<(), String> {
+    fn update_node(&mut self, node_id: &str, update: UpdateNodeRequest) -> Result<Node, String> {
+        let node = self.nodes.get_mut(node_id)
+            .ok_or_else(|| format!("Node '{}' does not exist", node_id))?;
+        if let Some(label) = update.label {
+            node.label = label;
+        }
+        if let Some(color) = update.color {
+            node.color = Some(color);
+        }
+        if let Some(size) = update.size {
+            node.size = Some(size);
+        }
+        if let Some(metadata) = update.metadata {
+            node.metadata = metadata;
+        }
+        Ok(node.clone())
+    }
+
+    fn update_edge(&mut self, edge_id: &str, update: UpdateEdgeRequest) -> Result<Edge, String> {
+        if !self.edges.contains_key(edge_id) {
+            return Err(format!("Edge '{}' does not exist", edge_id));
+        }
+        if let Some(source) = &update.source {
+            if !self.nodes.contains_key(source) {
+                return Err(format!("Source node '{}' does not exist", source));
+            }
+        }
+        if let Some(target) = &update.target {
+            if !self.nodes.contains_key(target) {
+                return Err(format!("Target node '{}' does not exist", target));
+            }
+        }
+
+        let edge = self.edges.get_mut(edge_id).unwrap();
+        if let Some(source) = update.source {
+            edge.source = source;
+        }
+        if let Some(target) = update.target {
+            edge.target = target;
+        }
+        if let Some(label) = update.label {
+            edge.label = Some(label);
+        }
+        if let Some(weight) = update.weight {
+            edge.weight = Some(weight);
+        }
+        if let Some(color) = update.color {
+            edge.color = Some(color);
+        }
+        if let Some(metadata) = update.metadata {
+            edge.metadata = metadata;
+        }
+        Ok(edge.clone())
+    }
+
+    /// Removes a node together with every edge touching it. Returns the ids
+    /// of those edges, sorted, so callers can report their removal too.
+    fn remove_node(&mut self, node_id: &str) -> Result<Vec<String>, String> {
         if !self.nodes.contains_key(node_id) {
             return Err(format!("Node '{}' does not exist", node_id));
         }
         
         // Remove all edges connected to this node
-        self.edges.retain(|_, edge| edge.source != node_id && edge.target != node_id);
+        let mut removed_edges: Vec<String> = self.edges.values()
+            .filter(|edge| edge.source == node_id || edge.target == node_id)
+            .map(|edge| edge.id.clone())
+            .collect();
+        removed_edges.sort();
+        for edge_id in &removed_edges {
+            self.edges.remove(edge_id);
+        }
         
         // Remove the node
         self.nodes.remove(node_id);
-        Ok(())
+        Ok(removed_edges)
     }
 
     fn remove_edge(&mut self, edge_id: &str) -> Result<(), String> {
@@ -139,12 +279,587 @@ impl Graph {
         self.nodes.clear();
         self.edges.clear();
     }
+
+    /// Renders the graph in Graphviz DOT. Nodes and edges are emitted in id
+    /// order so the output is stable across runs. Edge weights are fractional
+    /// influence strengths, which `dot` rejects as its integer `weight`, so
+    /// they are written unchanged as a custom `influence` attribute instead.
+    fn to_dot(&self) -> String {
+        let (keyword, operator) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        let mut dot = format!("{} G {{\n", keyword);
+
+        let mut nodes: Vec<&Node> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        for node in nodes {
+            let mut attrs = vec![format!("label={}", dot_quote(&node.label))];
+            if let Some(color) = &node.color {
+                attrs.push("style=filled".to_string());
+                attrs.push(format!("fillcolor={}", dot_quote(color)));
+            }
+            dot.push_str(&format!("    {} [{}];\n", dot_quote(&node.id), attrs.join(", ")));
+        }
+
+        let mut edges: Vec<&Edge> = self.edges.values().collect();
+        edges.sort_by(|a, b| a.id.cmp(&b.id));
+        for edge in edges {
+            let mut attrs = Vec::new();
+            if let Some(label) = &edge.label {
+                attrs.push(format!("label={}", dot_quote(label)));
+            }
+            if let Some(weight) = edge.weight {
+                attrs.push(format!("influence={}", weight));
+            }
+            if let Some(color) = &edge.color {
+                attrs.push(format!("color={}", dot_quote(color)));
+            }
+            let attrs = if attrs.is_empty() { String::new() } else { format!(" [{}]", attrs.join(", ")) };
+            dot.push_str(&format!("    {} {} {}{};\n", dot_quote(&edge.source), operator, dot_quote(&edge.target), attrs));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Clusters of nodes reachable from one another, largest first (ties
+    /// broken by smallest id). Edge direction is ignored, so directed graphs
+    /// get their weakly connected components and undirected graphs their
+    /// ordinary components. Isolated nodes come back as singletons.
+    fn connected_components(&self) -> Vec<Vec<String>> {
+        let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in self.edges.values() {
+            neighbors.entry(edge.source.as_str()).or_default().push(edge.target.as_str());
+            neighbors.entry(edge.target.as_str()).or_default().push(edge.source.as_str());
+        }
+
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut components = Vec::new();
+        for start in self.sorted_node_ids() {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut component = vec![start.to_string()];
+            let mut queue = VecDeque::from([start]);
+            while let Some(current) = queue.pop_front() {
+                for &neighbor in neighbors.get(current).into_iter().flatten() {
+                    if visited.insert(neighbor) {
+                        component.push(neighbor.to_string());
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+        components
+    }
+
+    /// Every cycle found by a depth-first search that keeps the current path
+    /// on a stack; each back edge yields the path slice it closes, in
+    /// traversal order. Empty for a DAG. In undirected mode an edge is never
+    /// walked back the way it was entered, so single edges don't count.
+    ///
+    /// The search is iterative with an explicit frame stack, so long chains
+    /// cannot overflow the thread stack.
+    fn find_cycles(&self) -> Vec<Vec<String>> {
+        let adjacency = self.adjacency();
+        let no_neighbors = Vec::new();
+        let mut visited: HashSet<&str> = HashSet::new();
+        // Current DFS path, plus each path node's position for O(1) lookups
+        let mut path: Vec<&str> = Vec::new();
+        let mut on_path: HashMap<&str, usize> = HashMap::new();
+        // (node, edge it was entered by, index of the next neighbor to try)
+        let mut frames: Vec<(&str, Option<&str>, usize)> = Vec::new();
+        let mut cycles = Vec::new();
+
+        for start in self.sorted_node_ids() {
+            if visited.contains(start) {
+                continue;
+            }
+            visited.insert(start);
+            on_path.insert(start, path.len());
+            path.push(start);
+            frames.push((start, None, 0));
+
+            while let Some(frame) = frames.last_mut() {
+                let (node, via_edge, next) = *frame;
+                let neighbors = adjacency.get(node).unwrap_or(&no_neighbors);
+                let Some(&(edge_id, neighbor)) = neighbors.get(next) else {
+                    frames.pop();
+                    path.pop();
+                    on_path.remove(node);
+                    continue;
+                };
+                frame.2 += 1;
+
+                if Some(edge_id) == via_edge {
+                    continue;
+                }
+                if let Some(&position) = on_path.get(neighbor) {
+                    cycles.push(path[position..].iter().map(|n| n.to_string()).collect());
+                } else if visited.insert(neighbor) {
+                    on_path.insert(neighbor, path.len());
+                    path.push(neighbor);
+                    frames.push((neighbor, Some(edge_id), 0));
+                }
+            }
+        }
+        cycles
+    }
+
+    /// Whether adding an edge `source -> target` would close a cycle.
+    fn would_create_cycle(&self, source: &str, target: &str) -> bool {
+        source == target || self.shortest_path(target, source).is_some()
+    }
+
+    /// Induced subgraph of the nodes whose metadata `key` equals `value`.
+    /// String values are compared directly; other JSON values match when
+    /// `value` parses to the same JSON (so `value=3` matches the number 3). Edges are kept only when
+    /// both endpoints match, so the result is a valid graph on its own.
+    fn subgraph_by_metadata(&self, key: &str, value: &str) -> Graph {
+        let matches = |metadata: &Metadata| match metadata.get(key) {
+            Some(serde_json::Value::String(s)) => s == value,
+            Some(other) => serde_json::from_str::<serde_json::Value>(value).is_ok_and(|parsed| parsed == *other),
+            None => false,
+        };
+
+        let nodes: HashMap<String, Node> = self.nodes.iter()
+            .filter(|(_, node)| matches(&node.metadata))
+            .map(|(id, node)| (id.clone(), node.clone()))
+            .collect();
+        let edges = self.edges.iter()
+            .filter(|(_, edge)| nodes.contains_key(&edge.source) && nodes.contains_key(&edge.target))
+            .map(|(id, edge)| (id.clone(), edge.clone()))
+            .collect();
+
+        Graph { nodes, edges, directed: self.directed }
+    }
+
+    /// `(in, out)` degree of a node. In undirected mode every incident edge
+    /// counts once in both directions, so both numbers equal the plain degree.
+    fn degree(&self, node_id: &str) -> (usize, usize) {
+        let mut in_degree = 0;
+        let mut out_degree = 0;
+        for edge in self.edges.values() {
+            let touches = edge.source == node_id || edge.target == node_id;
+            if !self.directed {
+                if touches {
+                    in_degree += 1;
+                    out_degree += 1;
+                }
+                continue;
+            }
+            if edge.target == node_id {
+                in_degree += 1;
+            }
+            if edge.source == node_id {
+                out_degree += 1;
+            }
+        }
+        (in_degree, out_degree)
+    }
+
+    /// Degree statistics for every node, most central first (ties by id).
+    /// Centrality is normalized degree centrality: the node's degree divided
+    /// by the most it could have in a simple graph of this size.
+    fn degree_stats(&self) -> Vec<NodeStats> {
+        let max_degree = self.nodes.len().saturating_sub(1) as f64 * if self.directed { 2.0 } else { 1.0 };
+        let mut stats: Vec<NodeStats> = self.nodes.values()
+            .map(|node| {
+                let (in_degree, out_degree) = self.degree(&node.id);
+                let degree = if self.directed { in_degree + out_degree } else { in_degree };
+                NodeStats {
+                    id: node.id.clone(),
+                    label: node.label.clone(),
+                    in_degree,
+                    out_degree,
+                    centrality: if max_degree > 0.0 { degree as f64 / max_degree } else { 0.0 },
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| b.centrality.total_cmp(&a.centrality).then_with(|| a.id.cmp(&b.id)));
+        stats
+    }
+
+    /// Groups of node ids that share the same label. Only labels used by more
+    /// than one node are reported; ids within a group and the groups themselves
+    /// are sorted.
+    fn duplicate_labels(&self) -> Vec<Vec<String>> {
+        let mut by_label: HashMap<&str, Vec<String>> = HashMap::new();
+        for node in self.nodes.values() {
+            by_label.entry(node.label.as_str()).or_default().push(node.id.clone());
+        }
+        let mut groups: Vec<Vec<String>> = by_label.into_values()
+            .filter(|ids| ids.len() > 1)
+            .map(|mut ids| {
+                ids.sort();
+                ids
+            })
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    /// Merges every group of same-label nodes into the group's first id.
+    /// The survivor absorbs the merged nodes' metadata keys and fills in its
+    /// own missing color, size and position from them, keeping its own values
+    /// on conflict. Edges touching a merged node are re-pointed at the
+    /// survivor; an edge that would become a self-loop, or would duplicate
+    /// another edge's endpoints and label, is dropped. Returns the merged groups.
+    fn merge_duplicate_labels(&mut self) -> Vec<Vec<String>> {
+        let groups = self.duplicate_labels();
+        for ids in &groups {
+            let mut survivor = self.nodes.remove(&ids[0]).expect("group ids exist");
+            for id in &ids[1..] {
+                let merged = self.nodes.remove(id).expect("group ids exist");
+                for (key, value) in merged.metadata {
+                    survivor.metadata.entry(key).or_insert(value);
+                }
+                survivor.color = survivor.color.or(merged.color);
+                survivor.size = survivor.size.or(merged.size);
+                if survivor.x.is_none() && survivor.y.is_none() {
+                    survivor.x = merged.x;
+                    survivor.y = merged.y;
+                }
+            }
+            self.nodes.insert(survivor.id.clone(), survivor);
+        }
+        let replacements: HashMap<String, String> = groups.iter()
+            .flat_map(|ids| ids[1..].iter().map(|id| (id.clone(), ids[0].clone())))
+            .collect();
+
+        let mut collapsed = Vec::new();
+        let mut repointed = Vec::new();
+        for edge in self.edges.values_mut() {
+            let source = replacements.get(&edge.source).cloned();
+            let target = replacements.get(&edge.target).cloned();
+            if source.is_none() && target.is_none() {
+                continue;
+            }
+            edge.source = source.unwrap_or_else(|| edge.source.clone());
+            edge.target = target.unwrap_or_else(|| edge.target.clone());
+            if edge.source == edge.target {
+                collapsed.push(edge.id.clone());
+            } else {
+                repointed.push(edge.id.clone());
+            }
+        }
+        for edge_id in collapsed {
+            self.edges.remove(&edge_id);
+        }
+
+        // Re-pointed edges that now parallel an existing edge are redundant.
+        // Untouched edges always stay; among re-pointed ones the lowest id wins.
+        let directed = self.directed;
+        let endpoints = |edge: &Edge| {
+            let (a, b) = if !directed && edge.target < edge.source {
+                (&edge.target, &edge.source)
+            } else {
+                (&edge.source, &edge.target)
+            };
+            (a.clone(), b.clone(), edge.label.clone())
+        };
+        let repointed_ids: HashSet<&String> = repointed.iter().collect();
+        let mut seen: HashSet<(String, String, Option<String>)> = self.edges.values()
+            .filter(|edge| !repointed_ids.contains(&edge.id))
+            .map(endpoints)
+            .collect();
+        repointed.sort();
+        for edge_id in &repointed {
+            if !seen.insert(endpoints(&self.edges[edge_id])) {
+                self.edges.remove(edge_id);
+            }
+        }
+        groups
+    }
+
+    /// Outgoing neighbors of every node as `(edge_id, neighbor_id)` pairs,
+    /// sorted by edge id so traversals are deterministic. In undirected mode
+    /// every edge is listed from both of its endpoints.
+    fn adjacency(&self) -> HashMap<&str, Vec<(&str, &str)>> {
+        let mut adjacency: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+        for edge in self.edges.values() {
+            adjacency
+                .entry(edge.source.as_str())
+                .or_default()
+                .push((edge.id.as_str(), edge.target.as_str()));
+            if !self.directed && edge.source != edge.target {
+                adjacency
+                    .entry(edge.target.as_str())
+                    .or_default()
+                    .push((edge.id.as_str(), edge.source.as_str()));
+            }
+        }
+        for neighbors in adjacency.values_mut() {
+            neighbors.sort();
+        }
+        adjacency
+    }
+
+    /// Shortest path from `from` to `to` by hop count (BFS, honoring the
+    /// directed/undirected mode).
+    /// Returns the node ids along the path, including both endpoints.
+    fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        if !self.nodes.contains_key(from) || !self.nodes.contains_key(to) {
+            return None;
+        }
+        if from == to {
+            return Some(vec![from.to_string()]);
+        }
+
+        let adjacency = self.adjacency();
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut visited: HashSet<&str> = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            for &(_, neighbor) in adjacency.get(current).into_iter().flatten() {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                previous.insert(neighbor, current);
+                if neighbor == to {
+                    let mut path = vec![to.to_string()];
+                    let mut cursor = to;
+                    while let Some(&prev) = previous.get(cursor) {
+                        path.push(prev.to_string());
+                        cursor = prev;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back(neighbor);
+            }
+        }
+        None
+    }
+
+    /// Initial node coordinates computed server-side. Both algorithms are
+    /// deterministic: nodes are processed in id order and the force layout
+    /// starts from id-seeded positions (see `force_layout`).
+    fn layout(&self, algo: LayoutAlgorithm) -> HashMap<String, (f64, f64)> {
+        match algo {
+            LayoutAlgorithm::Circular => self.circular_layout(),
+            LayoutAlgorithm::Force => self.force_layout(FORCE_ITERATIONS),
+        }
+    }
+
+    fn sorted_node_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.nodes.keys().map(String::as_str).collect();
+        ids.sort();
+        ids
+    }
+
+    fn circular_layout(&self) -> HashMap<String, (f64, f64)> {
+        let ids = self.sorted_node_ids();
+        let count = ids.len() as f64;
+        ids.into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let angle = 2.0 * std::f64::consts::PI * i as f64 / count;
+                (id.to_string(), (LAYOUT_RADIUS * angle.cos(), LAYOUT_RADIUS * angle.sin()))
+            })
+            .collect()
+    }
+
+    /// Force-directed layout. Initial positions are derived from a hash of
+    /// each node id, so the same graph always converges to the same layout
+    /// regardless of any positions stored by a previous run.
+    fn force_layout(&self, iterations: usize) -> HashMap<String, (f64, f64)> {
+        let ids = self.sorted_node_ids();
+        let initial = ids.iter()
+            .map(|id| {
+                let mut rng = LayoutRng(fnv1a(id));
+                ((rng.next_f64() * 2.0 - 1.0) * LAYOUT_RADIUS, (rng.next_f64() * 2.0 - 1.0) * LAYOUT_RADIUS)
+            })
+            .collect();
+        let positions = self.fruchterman_reingold(&ids, initial, iterations);
+
+        ids.into_iter()
+            .map(str::to_string)
+            .zip(positions)
+            .collect()
+    }
+
+    /// Stores computed positions on each node's `x`/`y`. Positions for nodes
+    /// that no longer exist (removed while the layout ran) are ignored.
+    /// Returns the ids of the nodes that were updated, sorted.
+    fn apply_layout(&mut self, positions: &HashMap<String, (f64, f64)>) -> Vec<String> {
+        let mut updated = Vec::new();
+        for (id, &(x, y)) in positions {
+            if let Some(node) = self.nodes.get_mut(id) {
+                node.x = Some(x);
+                node.y = Some(y);
+                updated.push(id.clone());
+            }
+        }
+        updated.sort();
+        updated
+    }
+
+    /// Fruchterman-Reingold style iteration: every pair of nodes repels, every
+    /// edge attracts, and the maximum displacement cools linearly. `positions`
+    /// holds the starting coordinates of `ids`, index for index.
+    fn fruchterman_reingold(&self, ids: &[&str], mut positions: Vec<(f64, f64)>, iterations: usize) -> Vec<(f64, f64)> {
+        if ids.is_empty() {
+            return positions;
+        }
+
+        let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let mut edge_pairs: Vec<(usize, usize)> = self.edges.values()
+            .filter_map(|edge| Some((*index.get(edge.source.as_str())?, *index.get(edge.target.as_str())?)))
+            .filter(|(a, b)| a != b)
+            .collect();
+        edge_pairs.sort();
+
+        let area = (2.0 * LAYOUT_RADIUS).powi(2);
+        let k = (area / ids.len() as f64).sqrt();
+
+        for iteration in 0..iterations {
+            let mut displacement = vec![(0.0, 0.0); positions.len()];
+
+            for i in 0..positions.len() {
+                for j in (i + 1)..positions.len() {
+                    let dx = positions[i].0 - positions[j].0;
+                    let dy = positions[i].1 - positions[j].1;
+                    let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                    let force = k * k / distance;
+                    displacement[i].0 += dx / distance * force;
+                    displacement[i].1 += dy / distance * force;
+                    displacement[j].0 -= dx / distance * force;
+                    displacement[j].1 -= dy / distance * force;
+                }
+            }
+
+            for &(a, b) in &edge_pairs {
+                let dx = positions[a].0 - positions[b].0;
+                let dy = positions[a].1 - positions[b].1;
+                let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                let force = distance * distance / k;
+                displacement[a].0 -= dx / distance * force;
+                displacement[a].1 -= dy / distance * force;
+                displacement[b].0 += dx / distance * force;
+                displacement[b].1 += dy / distance * force;
+            }
+
+            let temperature = LAYOUT_RADIUS * 0.1 * (1.0 - iteration as f64 / iterations as f64);
+            for (position, (dx, dy)) in positions.iter_mut().zip(displacement) {
+                let length = (dx * dx + dy * dy).sqrt();
+                if length > 0.0 {
+                    let step = length.min(temperature);
+                    position.0 = (position.0 + dx / length * step).clamp(-LAYOUT_RADIUS, LAYOUT_RADIUS);
+                    position.1 = (position.1 + dy / length * step).clamp(-LAYOUT_RADIUS, LAYOUT_RADIUS);
+                }
+            }
+        }
+
+        positions
+    }
+
+    /// Ids of the edges connecting consecutive nodes of `path`. Where several
+    /// edges join the same pair, the one with the smallest id is used.
+    fn edges_along(&self, path: &[String]) -> Vec<String> {
+        let adjacency = self.adjacency();
+        path.windows(2)
+            .filter_map(|hop| {
+                adjacency.get(hop[0].as_str())?
+                    .iter()
+                    .find(|(_, neighbor)| *neighbor == hop[1])
+                    .map(|(edge_id, _)| edge_id.to_string())
+            })
+            .collect()
+    }
+}
+
+/// A single mutation of the live graph, as reported to polling clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GraphEvent {
+    NodeAdded { node: Node },
+    EdgeAdded { edge: Edge },
+    NodeUpdated { node: Node },
+    EdgeUpdated { edge: Edge },
+    NodeRemoved { id: String },
+    EdgeRemoved { id: String },
+    Cleared,
+    ModeChanged { directed: bool },
+    /// New `x`/`y` for many nodes at once, so one layout run is a single
+    /// event rather than one `node_updated` per node.
+    LayoutComputed { positions: HashMap<String, (f64, f64)> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: GraphEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangesResponse {
+    /// Sequence number of the most recent mutation; pass it as `since` on the next poll.
+    pub sequence: u64,
+    pub events: Vec<SequencedEvent>,
+    /// Set when the requested events are no longer buffered (or the server
+    /// restarted); the client should refetch `/api/graph` and continue from `sequence`.
+    pub reset_required: bool,
 }
 
+const DEFAULT_EVENT_CAPACITY: usize = 1000;
+/// Environment variable overriding how many events `/api/changes` buffers.
+const EVENT_CAPACITY_ENV: &str = "GRAPH_EVENT_CAPACITY";
+/// Leading dot keeps it clear of project files: the pre-index naming scheme
+/// mapped `.` to `_`, so no legacy project can be stored under this name.
+const PROJECT_INDEX_FILE: &str = ".index.json";
+const LIVE_CHANNEL_CAPACITY: usize = 256;
+
 struct GraphState {
     graph: Graph,
     save_path: PathBuf,
     projects_path: PathBuf,
+    sequence: u64,
+    events: VecDeque<SequencedEvent>,
+    event_capacity: usize,
+    /// Allowed edge labels. `None` means labels are unrestricted.
+    edge_ontology: Option<HashSet<String>>,
+    /// Live feed of mutations for `/api/ws` subscribers.
+    live_events: broadcast::Sender<GraphEvent>,
+}
+
+/// Event buffer size from the `GRAPH_EVENT_CAPACITY` value, if set. Must be
+/// a positive integer; anything else is a startup error.
+fn parse_event_capacity(value: Option<&str>) -> Result<usize, String> {
+    let Some(value) = value else {
+        return Ok(DEFAULT_EVENT_CAPACITY);
+    };
+    match value.trim().parse::<usize>() {
+        Ok(capacity) if capacity > 0 => Ok(capacity),
+        _ => Err(format!("{} must be a positive integer, got '{}'", EVENT_CAPACITY_ENV, value)),
+    }
+}
+
+/// Environment variable overriding where the edge ontology is read from.
+const EDGE_ONTOLOGY_ENV: &str = "EDGE_ONTOLOGY_PATH";
+const DEFAULT_EDGE_ONTOLOGY_PATH: &str = "edge_ontology.json";
+
+/// Reads the allowed edge-label set from a JSON array of strings. Only a
+/// missing file disables ontology validation; a file that exists but cannot
+/// be read or parsed is an error, so a broken config never silently lets
+/// every label through.
+fn load_edge_ontology(path: &PathBuf) -> Result<Option<HashSet<String>>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            info!("No edge ontology at {:?}, labels will not be validated", path);
+            return Ok(None);
+        }
+        Err(e) => return Err(format!("Failed to read edge ontology {:?}: {}", path, e)),
+    };
+    let labels = serde_json::from_str::<HashSet<String>>(&content)
+        .map_err(|e| format!("Failed to parse edge ontology {:?}: {}", path, e))?;
+    info!("Loaded edge ontology with {} labels", labels.len());
+    Ok(Some(labels))
 }
 
 impl GraphState {
@@ -157,23 +872,136 @@ impl GraphState {
             warn!("Failed to create projects directory: {}", e);
         }
         
-        Self { graph, save_path, projects_path }
+        Self {
+            graph,
+            save_path,
+            projects_path,
+            sequence: 0,
+            events: VecDeque::new(),
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+            edge_ontology: None,
+            live_events: broadcast::channel(LIVE_CHANNEL_CAPACITY).0,
+        }
     }
     
     fn save(&self) -> Result<(), String> {
         self.graph.save_to_file(&self.save_path)
     }
+
+    /// Checks an edge label against the configured ontology. Unlabeled edges
+    /// are always accepted.
+    fn validate_edge_label(&self, label: Option<&str>) -> Result<(), String> {
+        let (Some(ontology), Some(label)) = (&self.edge_ontology, label) else {
+            return Ok(());
+        };
+        if ontology.contains(label) {
+            return Ok(());
+        }
+        let mut allowed: Vec<&str> = ontology.iter().map(String::as_str).collect();
+        allowed.sort();
+        Err(format!("Edge label '{}' is not in the ontology; allowed labels: {}", label, allowed.join(", ")))
+    }
+
+    /// Appends a mutation to the polling buffer and pushes it to any live
+    /// subscribers. Handlers call this after persisting the change.
+    fn record_event(&mut self, event: GraphEvent) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.live_events.send(event.clone());
+        self.sequence += 1;
+        self.events.push_back(SequencedEvent { sequence: self.sequence, event });
+        while self.events.len() > self.event_capacity {
+            self.events.pop_front();
+        }
+    }
+
+    fn changes_since(&self, since: u64) -> ChangesResponse {
+        let oldest_available = self.events.front().map_or(self.sequence + 1, |e| e.sequence);
+        if since > self.sequence || since + 1 < oldest_available {
+            return ChangesResponse {
+                sequence: self.sequence,
+                events: Vec::new(),
+                reset_required: true,
+            };
+        }
+
+        ChangesResponse {
+            sequence: self.sequence,
+            events: self.events.iter().filter(|e| e.sequence > since).cloned().collect(),
+            reset_required: false,
+        }
+    }
     
+    /// Display name -> file name for every stored project. When no index
+    /// exists yet (projects saved before it was introduced) it is rebuilt from
+    /// the `name` recorded inside each project file.
+    fn project_index(&self) -> HashMap<String, String> {
+        let index_path = self.projects_path.join(PROJECT_INDEX_FILE);
+        if let Ok(content) = fs::read_to_string(&index_path) {
+            match serde_json::from_str(&content) {
+                Ok(index) => return index,
+                Err(e) => warn!("Failed to parse project index, rebuilding: {}", e),
+            }
+        }
+
+        let mut index = HashMap::new();
+        let Ok(entries) = fs::read_dir(&self.projects_path) else {
+            return index;
+        };
+        for entry in entries.flatten() {
+            let Some(filename) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if !filename.ends_with(".json") || filename == PROJECT_INDEX_FILE {
+                continue;
+            }
+            let name = fs::read_to_string(entry.path()).ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .and_then(|data| data.get("name")?.as_str().map(str::to_string))
+                .unwrap_or_else(|| filename.trim_end_matches(".json").to_string());
+            index.insert(name, filename);
+        }
+        index
+    }
+
+    fn write_project_index(&self, index: &HashMap<String, String>) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(index)
+            .map_err(|e| format!("Failed to serialize project index: {}", e))?;
+        fs::write(self.projects_path.join(PROJECT_INDEX_FILE), content).map_err(|e| {
+            error!("Failed to write project index: {}", e);
+            format!("Failed to write project index: {}", e)
+        })
+    }
+
+    /// Readable slug plus a hash of the exact name, so names that slug to the
+    /// same string (`my:proj`, `my/proj`) still get distinct files.
+    fn new_project_file_name(name: &str, index: &HashMap<String, String>) -> String {
+        let slug = name.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
+        let base = format!("{}-{:016x}", slug, fnv1a(name));
+        let taken: HashSet<&str> = index.values().map(String::as_str).collect();
+        let mut candidate = format!("{}.json", base);
+        let mut suffix = 1;
+        while taken.contains(candidate.as_str()) || candidate == PROJECT_INDEX_FILE {
+            candidate = format!("{}-{}.json", base, suffix);
+            suffix += 1;
+        }
+        candidate
+    }
+
     fn save_project(&self, project_data: &ProjectData) -> Result<(), String> {
-        let project_file = self.projects_path.join(format!("{}.json", 
-            project_data.name.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_")));
+        let mut index = self.project_index();
+        let file_name = match index.get(&project_data.name) {
+            Some(file_name) => file_name.clone(),
+            None => Self::new_project_file_name(&project_data.name, &index),
+        };
+        let project_file = self.projects_path.join(&file_name);
         
         match serde_json::to_string_pretty(project_data) {
             Ok(content) => {
                 match fs::write(&project_file, content) {
                     Ok(()) => {
                         info!("Saved project '{}' to file: {:?}", project_data.name, project_file);
-                        Ok(())
+                        index.insert(project_data.name.clone(), file_name);
+                        self.write_project_index(&index)
                     }
                     Err(e) => {
                         error!("Failed to write project file: {}", e);
@@ -189,12 +1017,17 @@ impl GraphState {
     }
     
     fn load_project(&self, project_name: &str) -> Result<ProjectData, String> {
-        let project_file = self.projects_path.join(format!("{}.json", 
-            project_name.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_")));
+        let Some(file_name) = self.project_index().remove(project_name) else {
+            return Err(format!("Project '{}' not found", project_name));
+        };
+        let project_file = self.projects_path.join(file_name);
         
         match fs::read_to_string(&project_file) {
             Ok(content) => {
-                match serde_json::from_str::<ProjectData>(&content) {
+                let parsed = serde_json::from_str::<serde_json::Value>(&content)
+                    .map_err(|e| e.to_string())
+                    .and_then(|data| ProjectData::migrate(data).map_err(|e| e.to_string()));
+                match parsed {
                     Ok(project) => {
                         info!("Loaded project '{}' from file: {:?}", project_name, project_file);
                         Ok(project)
@@ -213,37 +1046,22 @@ impl GraphState {
     }
     
     fn list_projects(&self) -> Result<Vec<String>, String> {
-        match fs::read_dir(&self.projects_path) {
-            Ok(entries) => {
-                let mut projects = Vec::new();
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if let Some(filename) = entry.file_name().to_str() {
-                            if filename.ends_with(".json") {
-                                let project_name = filename.trim_end_matches(".json").to_string();
-                                projects.push(project_name);
-                            }
-                        }
-                    }
-                }
-                projects.sort();
-                Ok(projects)
-            }
-            Err(e) => {
-                warn!("Failed to read projects directory: {}", e);
-                Ok(Vec::new())
-            }
-        }
+        let mut projects: Vec<String> = self.project_index().into_keys().collect();
+        projects.sort();
+        Ok(projects)
     }
     
     fn delete_project(&self, project_name: &str) -> Result<(), String> {
-        let project_file = self.projects_path.join(format!("{}.json", 
-            project_name.replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_")));
+        let mut index = self.project_index();
+        let Some(file_name) = index.remove(project_name) else {
+            return Err(format!("Project '{}' not found", project_name));
+        };
+        let project_file = self.projects_path.join(file_name);
         
         match fs::remove_file(&project_file) {
             Ok(()) => {
                 info!("Deleted project '{}': {:?}", project_name, project_file);
-                Ok(())
+                self.write_project_index(&index)
             }
             Err(e) => {
                 error!("Failed to delete project file: {}", e);
@@ -261,7 +1079,7 @@ struct AddNodeRequest {
     label: String,
     color: Option<String>,
     size: Option<f64>,
-    metadata: Option<HashMap<String, String>>,
+    metadata: Option<Metadata>,
 }
 
 #[derive(Deserialize)]
@@ -272,7 +1090,110 @@ struct AddEdgeRequest {
     label: Option<String>,
     weight: Option<f64>,
     color: Option<String>,
-    metadata: Option<HashMap<String, String>>,
+    metadata: Option<Metadata>,
+    /// Refuse the edge if it would close a cycle.
+    #[serde(default)]
+    reject_cycles: bool,
+}
+
+#[derive(Deserialize)]
+struct PathQuery {
+    from: String,
+    to: String,
+}
+
+impl AddNodeRequest {
+    fn into_node(self) -> Node {
+        Node {
+            id: self.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            label: self.label,
+            color: self.color,
+            size: self.size,
+            metadata: self.metadata.unwrap_or_default(),
+            x: None,
+            y: None,
+        }
+    }
+}
+
+impl AddEdgeRequest {
+    fn into_edge(self) -> Edge {
+        Edge {
+            id: self.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            source: self.source,
+            target: self.target,
+            label: self.label,
+            weight: self.weight,
+            color: self.color,
+            metadata: self.metadata.unwrap_or_default(),
+        }
+    }
+}
+
+/// Partial node update: only the fields present in the body are changed.
+#[derive(Deserialize)]
+struct UpdateNodeRequest {
+    label: Option<String>,
+    color: Option<String>,
+    size: Option<f64>,
+    metadata: Option<Metadata>,
+}
+
+/// Partial edge update: only the fields present in the body are changed.
+#[derive(Deserialize)]
+struct UpdateEdgeRequest {
+    source: Option<String>,
+    target: Option<String>,
+    label: Option<String>,
+    weight: Option<f64>,
+    color: Option<String>,
+    metadata: Option<Metadata>,
+}
+
+#[derive(Deserialize)]
+struct SetModeRequest {
+    directed: bool,
+}
+
+#[derive(Deserialize)]
+struct ComputeLayoutRequest {
+    iterations: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SubgraphQuery {
+    key: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChangesQuery {
+    since: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct LayoutQuery {
+    algo: Option<LayoutAlgorithm>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStats {
+    pub id: String,
+    pub label: String,
+    pub in_degree: usize,
+    pub out_degree: usize,
+    pub centrality: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathResult {
+    pub nodes: Vec<String>,
+    pub edges: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -282,8 +1203,13 @@ struct ApiResponse<T> {
     error: Option<String>,
 }
 
+/// Version written into every saved project. Bump it alongside a new step in
+/// `ProjectData::migrate` whenever the stored shape changes.
+pub const PROJECT_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectData {
+    pub schema_version: u32,
     pub name: String,
     pub nodes: HashMap<String, Node>,
     pub edges: HashMap<String, Edge>,
@@ -291,9 +1217,180 @@ pub struct ProjectData {
     pub timestamp: String,
 }
 
-#[derive(Deserialize)]
-struct SaveProjectRequest {
-    name: String,
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The file was written by a newer server than this one.
+    UnsupportedVersion(u32),
+    /// The record is not an object or is missing required fields.
+    Invalid(String),
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::UnsupportedVersion(version) => write!(
+                f, "Unsupported project schema version {} (newest supported is {})",
+                version, PROJECT_SCHEMA_VERSION
+            ),
+            MigrationError::Invalid(reason) => write!(f, "Invalid project data: {}", reason),
+        }
+    }
+}
+
+impl ProjectData {
+    /// Upgrades a stored project record, one version step at a time, to the
+    /// current shape. Records without a `schema_version` are treated as v0.
+    pub fn migrate(mut data: serde_json::Value) -> Result<ProjectData, MigrationError> {
+        let object = data.as_object_mut()
+            .ok_or_else(|| MigrationError::Invalid("expected a JSON object".to_string()))?;
+        let mut version = match object.get("schema_version") {
+            None => 0,
+            Some(value) => value.as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| MigrationError::Invalid("schema_version must be an unsigned integer".to_string()))?,
+        };
+        if version > PROJECT_SCHEMA_VERSION {
+            return Err(MigrationError::UnsupportedVersion(version));
+        }
+
+        if version == 0 {
+            // v0 -> v1: introduce schema_version and default optional fields
+            for field in ["nodes", "edges"] {
+                object.entry(field).or_insert_with(|| serde_json::json!({}));
+            }
+            object.entry("config").or_insert(serde_json::Value::Null);
+            object.entry("timestamp").or_insert_with(|| serde_json::json!("0"));
+            version = 1;
+        }
+
+        object.insert("schema_version".to_string(), serde_json::json!(version));
+        serde_json::from_value(data).map_err(|e| MigrationError::Invalid(e.to_string()))
+    }
+
+    /// Merges incoming nodes and edges into this project. Node-id collisions
+    /// are resolved by `strategy`; renamed nodes get a fresh `<id>-<n>` id and
+    /// incoming edges are rewired to follow them. Edge-id collisions use the
+    /// same strategy. Any edge left pointing at a missing node is dropped, so
+    /// the result is always a valid graph.
+    pub fn merge(
+        &mut self,
+        nodes: HashMap<String, Node>,
+        edges: HashMap<String, Edge>,
+        strategy: ConflictStrategy,
+    ) -> MergeSummary {
+        let mut summary = MergeSummary::default();
+        let mut incoming_nodes: Vec<Node> = nodes.into_values().collect();
+        incoming_nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        let incoming_ids: HashSet<String> = incoming_nodes.iter().map(|n| n.id.clone()).collect();
+
+        // Incoming node id -> id it ended up under in this project
+        let mut id_map: HashMap<String, String> = HashMap::new();
+        for mut node in incoming_nodes {
+            let original_id = node.id.clone();
+            if !self.nodes.contains_key(&original_id) {
+                summary.nodes_added += 1;
+            } else {
+                match strategy {
+                    ConflictStrategy::Skip => {
+                        summary.nodes_skipped += 1;
+                        id_map.insert(original_id.clone(), original_id);
+                        continue;
+                    }
+                    ConflictStrategy::Overwrite => summary.nodes_overwritten += 1,
+                    ConflictStrategy::Rename => {
+                        node.id = unique_id(&original_id, |id| {
+                            self.nodes.contains_key(id) || incoming_ids.contains(id)
+                        });
+                        summary.nodes_renamed += 1;
+                    }
+                }
+            }
+            id_map.insert(original_id, node.id.clone());
+            self.nodes.insert(node.id.clone(), node);
+        }
+
+        let mut incoming_edges: Vec<Edge> = edges.into_values().collect();
+        incoming_edges.sort_by(|a, b| a.id.cmp(&b.id));
+        let incoming_edge_ids: HashSet<String> = incoming_edges.iter().map(|e| e.id.clone()).collect();
+        for mut edge in incoming_edges {
+            edge.source = id_map.get(&edge.source).cloned().unwrap_or(edge.source);
+            edge.target = id_map.get(&edge.target).cloned().unwrap_or(edge.target);
+            if !self.nodes.contains_key(&edge.source) || !self.nodes.contains_key(&edge.target) {
+                summary.edges_skipped += 1;
+                continue;
+            }
+            if self.edges.contains_key(&edge.id) {
+                match strategy {
+                    ConflictStrategy::Skip => {
+                        summary.edges_skipped += 1;
+                        continue;
+                    }
+                    ConflictStrategy::Overwrite => {}
+                    ConflictStrategy::Rename => {
+                        edge.id = unique_id(&edge.id, |id| {
+                            self.edges.contains_key(id) || incoming_edge_ids.contains(id)
+                        });
+                    }
+                }
+            }
+            summary.edges_added += 1;
+            self.edges.insert(edge.id.clone(), edge);
+        }
+
+        // The target project itself may already hold dangling edges
+        let before = self.edges.len();
+        let nodes = &self.nodes;
+        self.edges.retain(|_, edge| nodes.contains_key(&edge.source) && nodes.contains_key(&edge.target));
+        summary.edges_dropped = before - self.edges.len();
+
+        summary
+    }
+}
+
+/// First `<base>-<n>` (n >= 2) for which `taken` is false.
+fn unique_id(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap()
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    #[default]
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MergeSummary {
+    pub nodes_added: usize,
+    pub nodes_skipped: usize,
+    pub nodes_overwritten: usize,
+    pub nodes_renamed: usize,
+    pub edges_added: usize,
+    pub edges_skipped: usize,
+    /// Pre-existing edges of the target project that referenced missing nodes.
+    pub edges_dropped: usize,
+}
+
+/// Merge source: either another saved project or inline nodes/edges.
+#[derive(Deserialize)]
+struct MergeProjectRequest {
+    source: Option<String>,
+    #[serde(default)]
+    nodes: HashMap<String, Node>,
+    #[serde(default)]
+    edges: HashMap<String, Edge>,
+    #[serde(default)]
+    conflict_strategy: ConflictStrategy,
+}
+
+#[derive(Deserialize)]
+struct SaveProjectRequest {
+    name: String,
     nodes: HashMap<String, Node>,
     edges: HashMap<String, Edge>,
     config: Option<HashMap<String, serde_json::Value>>,
@@ -326,13 +1423,7 @@ async fn add_node(
     State(graph_state): State<SharedGraphState>,
     Json(req): Json<AddNodeRequest>,
 ) -> Result<Json<ApiResponse<Node>>, StatusCode> {
-    let node = Node {
-        id: req.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
-        label: req.label,
-        color: req.color,
-        size: req.size,
-        metadata: req.metadata.unwrap_or_default(),
-    };
+    let node = req.into_node();
 
     let mut state = graph_state.write().unwrap();
     match state.graph.add_node(node.clone()) {
@@ -341,6 +1432,7 @@ async fn add_node(
             if let Err(e) = state.save() {
                 warn!("Failed to save graph after adding node: {}", e);
             }
+            state.record_event(GraphEvent::NodeAdded { node: node.clone() });
             Ok(Json(ApiResponse::success(node)))
         }
         Err(e) => {
@@ -354,23 +1446,26 @@ async fn add_edge(
     State(graph_state): State<SharedGraphState>,
     Json(req): Json<AddEdgeRequest>,
 ) -> Result<Json<ApiResponse<Edge>>, StatusCode> {
-    let edge = Edge {
-        id: req.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
-        source: req.source,
-        target: req.target,
-        label: req.label,
-        weight: req.weight,
-        color: req.color,
-        metadata: req.metadata.unwrap_or_default(),
-    };
+    let reject_cycles = req.reject_cycles;
+    let edge = req.into_edge();
 
     let mut state = graph_state.write().unwrap();
+    if let Err(e) = state.validate_edge_label(edge.label.as_deref()) {
+        warn!("Rejected edge: {}", e);
+        return Ok(Json(ApiResponse::error(e)));
+    }
+    if reject_cycles && state.graph.would_create_cycle(&edge.source, &edge.target) {
+        let e = format!("Edge {} -> {} would create a cycle", edge.source, edge.target);
+        warn!("Rejected edge: {}", e);
+        return Ok(Json(ApiResponse::error(e)));
+    }
     match state.graph.add_edge(edge.clone()) {
         Ok(()) => {
             info!("Added edge: {} -> {}", edge.source, edge.target);
             if let Err(e) = state.save() {
                 warn!("Failed to save graph after adding edge: {}", e);
             }
+            state.record_event(GraphEvent::EdgeAdded { edge: edge.clone() });
             Ok(Json(ApiResponse::success(edge)))
         }
         Err(e) => {
@@ -380,17 +1475,134 @@ async fn add_edge(
     }
 }
 
+async fn update_node(
+    State(graph_state): State<SharedGraphState>,
+    Path(node_id): Path<String>,
+    Json(req): Json<UpdateNodeRequest>,
+) -> Json<ApiResponse<Node>> {
+    let mut state = graph_state.write().unwrap();
+    match state.graph.update_node(&node_id, req) {
+        Ok(node) => {
+            info!("Updated node: {}", node_id);
+            if let Err(e) = state.save() {
+                warn!("Failed to save graph after updating node: {}", e);
+            }
+            state.record_event(GraphEvent::NodeUpdated { node: node.clone() });
+            Json(ApiResponse::success(node))
+        }
+        Err(e) => {
+            warn!("Failed to update node: {}", e);
+            Json(ApiResponse::error(e))
+        }
+    }
+}
+
+async fn update_edge(
+    State(graph_state): State<SharedGraphState>,
+    Path(edge_id): Path<String>,
+    Json(req): Json<UpdateEdgeRequest>,
+) -> Json<ApiResponse<Edge>> {
+    let mut state = graph_state.write().unwrap();
+    if let Err(e) = state.validate_edge_label(req.label.as_deref()) {
+        warn!("Rejected edge update: {}", e);
+        return Json(ApiResponse::error(e));
+    }
+    match state.graph.update_edge(&edge_id, req) {
+        Ok(edge) => {
+            info!("Updated edge: {}", edge_id);
+            if let Err(e) = state.save() {
+                warn!("Failed to save graph after updating edge: {}", e);
+            }
+            state.record_event(GraphEvent::EdgeUpdated { edge: edge.clone() });
+            Json(ApiResponse::success(edge))
+        }
+        Err(e) => {
+            warn!("Failed to update edge: {}", e);
+            Json(ApiResponse::error(e))
+        }
+    }
+}
+
+/// Adds every node or none: the batch is applied to a copy of the graph and
+/// only swapped in once all items succeed.
+async fn add_nodes_batch(
+    State(graph_state): State<SharedGraphState>,
+    Json(reqs): Json<Vec<AddNodeRequest>>,
+) -> Json<ApiResponse<Vec<Node>>> {
+    let nodes: Vec<Node> = reqs.into_iter().map(AddNodeRequest::into_node).collect();
+
+    let mut state = graph_state.write().unwrap();
+    let mut graph = state.graph.clone();
+    for (index, node) in nodes.iter().enumerate() {
+        if let Err(e) = graph.add_node(node.clone()) {
+            warn!("Rejected node batch at item {}: {}", index, e);
+            return Json(ApiResponse::error(format!("Item {}: {}", index, e)));
+        }
+    }
+
+    state.graph = graph;
+    info!("Added {} nodes in batch", nodes.len());
+    if let Err(e) = state.save() {
+        warn!("Failed to save graph after adding node batch: {}", e);
+    }
+    for node in &nodes {
+        state.record_event(GraphEvent::NodeAdded { node: node.clone() });
+    }
+    Json(ApiResponse::success(nodes))
+}
+
+/// Adds every edge or none, like `add_nodes_batch`.
+async fn add_edges_batch(
+    State(graph_state): State<SharedGraphState>,
+    Json(reqs): Json<Vec<AddEdgeRequest>>,
+) -> Json<ApiResponse<Vec<Edge>>> {
+    let reject_cycles: Vec<bool> = reqs.iter().map(|req| req.reject_cycles).collect();
+    let edges: Vec<Edge> = reqs.into_iter().map(AddEdgeRequest::into_edge).collect();
+
+    let mut state = graph_state.write().unwrap();
+    let mut graph = state.graph.clone();
+    for (index, edge) in edges.iter().enumerate() {
+        let result = state.validate_edge_label(edge.label.as_deref())
+            .and_then(|()| {
+                if reject_cycles[index] && graph.would_create_cycle(&edge.source, &edge.target) {
+                    Err(format!("Edge {} -> {} would create a cycle", edge.source, edge.target))
+                } else {
+                    Ok(())
+                }
+            })
+            .and_then(|()| graph.add_edge(edge.clone()));
+        if let Err(e) = result {
+            warn!("Rejected edge batch at item {}: {}", index, e);
+            return Json(ApiResponse::error(format!("Item {}: {}", index, e)));
+        }
+    }
+
+    state.graph = graph;
+    info!("Added {} edges in batch", edges.len());
+    if let Err(e) = state.save() {
+        warn!("Failed to save graph after adding edge batch: {}", e);
+    }
+    for edge in &edges {
+        state.record_event(GraphEvent::EdgeAdded { edge: edge.clone() });
+    }
+    Json(ApiResponse::success(edges))
+}
+
 async fn remove_node(
     State(graph_state): State<SharedGraphState>,
     Path(node_id): Path<String>,
 ) -> Json<ApiResponse<String>> {
     let mut state = graph_state.write().unwrap();
     match state.graph.remove_node(&node_id) {
-        Ok(()) => {
-            info!("Removed node: {}", node_id);
+        Ok(removed_edges) => {
+            info!("Removed node: {} ({} connected edges)", node_id, removed_edges.len());
             if let Err(e) = state.save() {
                 warn!("Failed to save graph after removing node: {}", e);
             }
+            for edge_id in removed_edges {
+                state.record_event(GraphEvent::EdgeRemoved { id: edge_id });
+            }
+            state.record_event(GraphEvent::NodeRemoved { id: node_id.clone() });
             Json(ApiResponse::success(format!("Node '{}' removed", node_id)))
         }
         Err(e) => {
@@ -411,6 +1623,7 @@ async fn remove_edge(
             if let Err(e) = state.save() {
                 warn!("Failed to save graph after removing edge: {}", e);
             }
+            state.record_event(GraphEvent::EdgeRemoved { id: edge_id.clone() });
             Json(ApiResponse::success(format!("Edge '{}' removed", edge_id)))
         }
         Err(e) => {
@@ -426,15 +1639,253 @@ async fn clear_graph(State(graph_state): State<SharedGraphState>) -> Json<ApiRes
     if let Err(e) = state.save() {
         warn!("Failed to save graph after clearing: {}", e);
     }
+    state.record_event(GraphEvent::Cleared);
     info!("Graph cleared");
     Json(ApiResponse::success("Graph cleared".to_string()))
 }
 
+async fn export_graph(
+    State(graph_state): State<SharedGraphState>,
+    Query(query): Query<ExportQuery>,
+) -> Result<String, (StatusCode, String)> {
+    let state = graph_state.read().unwrap();
+    match query.format.as_deref().unwrap_or("dot") {
+        "dot" => Ok(state.graph.to_dot()),
+        other => Err((StatusCode::BAD_REQUEST, format!("Unsupported export format '{}'", other))),
+    }
+}
+
+async fn get_subgraph(
+    State(graph_state): State<SharedGraphState>,
+    Query(query): Query<SubgraphQuery>,
+) -> Json<ApiResponse<Graph>> {
+    let state = graph_state.read().unwrap();
+    Json(ApiResponse::success(state.graph.subgraph_by_metadata(&query.key, &query.value)))
+}
+
+async fn get_stats(State(graph_state): State<SharedGraphState>) -> Json<ApiResponse<Vec<NodeStats>>> {
+    let state = graph_state.read().unwrap();
+    Json(ApiResponse::success(state.graph.degree_stats()))
+}
+
+async fn get_components(State(graph_state): State<SharedGraphState>) -> Json<ApiResponse<Vec<Vec<String>>>> {
+    let state = graph_state.read().unwrap();
+    Json(ApiResponse::success(state.graph.connected_components()))
+}
+
+async fn compute_and_store_layout(
+    State(graph_state): State<SharedGraphState>,
+    Json(req): Json<ComputeLayoutRequest>,
+) -> Json<ApiResponse<HashMap<String, (f64, f64)>>> {
+    let iterations = req.iterations.unwrap_or(FORCE_ITERATIONS);
+    if iterations > MAX_LAYOUT_ITERATIONS {
+        return Json(ApiResponse::error(format!(
+            "iterations must be at most {} (got {})", MAX_LAYOUT_ITERATIONS, iterations
+        )));
+    }
+
+    // The layout is O(nodes² × iterations): run it on a snapshot, off the
+    // async workers and without holding the lock
+    let graph = graph_state.read().unwrap().graph.clone();
+    let mut positions = match tokio::task::spawn_blocking(move || graph.force_layout(iterations)).await {
+        Ok(positions) => positions,
+        Err(e) => {
+            error!("Layout task failed: {}", e);
+            return Json(ApiResponse::error(format!("Layout task failed: {}", e)));
+        }
+    };
+
+    let mut state = graph_state.write().unwrap();
+    let updated = state.graph.apply_layout(&positions);
+    positions.retain(|id, _| state.graph.nodes.contains_key(id));
+    info!("Computed layout for {} nodes", updated.len());
+    if let Err(e) = state.save() {
+        warn!("Failed to save graph after computing layout: {}", e);
+    }
+
+    state.record_event(GraphEvent::LayoutComputed { positions: positions.clone() });
+    Json(ApiResponse::success(positions))
+}
+
+async fn get_cycles(State(graph_state): State<SharedGraphState>) -> Json<ApiResponse<Vec<Vec<String>>>> {
+    let state = graph_state.read().unwrap();
+    Json(ApiResponse::success(state.graph.find_cycles()))
+}
+
+async fn set_graph_mode(
+    State(graph_state): State<SharedGraphState>,
+    Json(req): Json<SetModeRequest>,
+) -> Json<ApiResponse<bool>> {
+    let mut state = graph_state.write().unwrap();
+    if state.graph.directed != req.directed {
+        state.graph.directed = req.directed;
+        info!("Graph mode set to {}", if req.directed { "directed" } else { "undirected" });
+        if let Err(e) = state.save() {
+            warn!("Failed to save graph after changing mode: {}", e);
+        }
+        state.record_event(GraphEvent::ModeChanged { directed: req.directed });
+    }
+    Json(ApiResponse::success(state.graph.directed))
+}
+
+async fn get_duplicates(State(graph_state): State<SharedGraphState>) -> Json<ApiResponse<Vec<Vec<String>>>> {
+    let state = graph_state.read().unwrap();
+    Json(ApiResponse::success(state.graph.duplicate_labels()))
+}
+
+async fn dedupe_nodes(State(graph_state): State<SharedGraphState>) -> Json<ApiResponse<Vec<Vec<String>>>> {
+    let mut state = graph_state.write().unwrap();
+    let edges_before = state.graph.edges.clone();
+    let groups = state.graph.merge_duplicate_labels();
+    if groups.is_empty() {
+        return Json(ApiResponse::success(groups));
+    }
+
+    info!("Merged {} groups of duplicate-label nodes", groups.len());
+    if let Err(e) = state.save() {
+        warn!("Failed to save graph after dedupe: {}", e);
+    }
+
+    let mut edge_ids: Vec<&String> = edges_before.keys().collect();
+    edge_ids.sort();
+    for edge_id in edge_ids {
+        let before = &edges_before[edge_id];
+        let after = state.graph.edges.get(edge_id).cloned();
+        match after {
+            Some(edge) if edge.source == before.source && edge.target == before.target => {}
+            Some(edge) => {
+                state.record_event(GraphEvent::EdgeRemoved { id: edge_id.clone() });
+                state.record_event(GraphEvent::EdgeAdded { edge });
+            }
+            None => state.record_event(GraphEvent::EdgeRemoved { id: edge_id.clone() }),
+        }
+    }
+    for ids in &groups {
+        let survivor = state.graph.nodes[&ids[0]].clone();
+        state.record_event(GraphEvent::NodeUpdated { node: survivor });
+        for id in &ids[1..] {
+            state.record_event(GraphEvent::NodeRemoved { id: id.clone() });
+        }
+    }
+
+    Json(ApiResponse::success(groups))
+}
+
+/// Pushes every subsequent graph mutation to the client as a JSON
+/// `GraphEvent`. There is no replay; clients fetch `/api/graph` once on connect.
+/// A client that falls too far behind is sent `{"type": "reset_required"}`
+/// and should refetch `/api/graph`, like a poller seeing `reset_required`.
+async fn live_updates(
+    State(graph_state): State<SharedGraphState>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let receiver = graph_state.read().unwrap().live_events.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, receiver))
+}
+
+async fn forward_events(mut socket: WebSocket, mut receiver: broadcast::Receiver<GraphEvent>) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("WebSocket subscriber lagged, {} events skipped; requesting reset", skipped);
+                        let reset = serde_json::json!({"type": "reset_required"}).to_string();
+                        if socket.send(Message::Text(reset)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let payload = match serde_json::to_string(&event) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        error!("Failed to serialize graph event: {}", e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(payload)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+async fn get_changes(
+    State(graph_state): State<SharedGraphState>,
+    Query(query): Query<ChangesQuery>,
+) -> Json<ApiResponse<ChangesResponse>> {
+    let state = graph_state.read().unwrap();
+    Json(ApiResponse::success(state.changes_since(query.since.unwrap_or(0))))
+}
+
+async fn find_path(
+    State(graph_state): State<SharedGraphState>,
+    Query(query): Query<PathQuery>,
+) -> Json<ApiResponse<PathResult>> {
+    let state = graph_state.read().unwrap();
+    for node_id in [&query.from, &query.to] {
+        if !state.graph.nodes.contains_key(node_id) {
+            warn!("Failed to find path: node '{}' does not exist", node_id);
+            return Json(ApiResponse::error(format!("Node '{}' does not exist", node_id)));
+        }
+    }
+
+    let nodes = state.graph.shortest_path(&query.from, &query.to).unwrap_or_default();
+    let edges = state.graph.edges_along(&nodes);
+    Json(ApiResponse::success(PathResult { nodes, edges }))
+}
+
+async fn find_path_between(
+    State(graph_state): State<SharedGraphState>,
+    Path((source, target)): Path<(String, String)>,
+) -> Json<ApiResponse<Vec<String>>> {
+    let state = graph_state.read().unwrap();
+    for node_id in [&source, &target] {
+        if !state.graph.nodes.contains_key(node_id) {
+            warn!("Failed to find path: node '{}' does not exist", node_id);
+            return Json(ApiResponse::error(format!("Node '{}' does not exist", node_id)));
+        }
+    }
+
+    match state.graph.shortest_path(&source, &target) {
+        Some(path) => Json(ApiResponse::success(path)),
+        None => Json(ApiResponse::error(format!("No path from '{}' to '{}'", source, target))),
+    }
+}
+
+async fn compute_layout(
+    State(graph_state): State<SharedGraphState>,
+    Query(query): Query<LayoutQuery>,
+) -> Json<ApiResponse<HashMap<String, (f64, f64)>>> {
+    // Force layout is O(nodes² × iterations), so compute it on a snapshot
+    // off the async workers instead of under the read lock
+    let graph = graph_state.read().unwrap().graph.clone();
+    let algo = query.algo.unwrap_or(LayoutAlgorithm::Force);
+    match tokio::task::spawn_blocking(move || graph.layout(algo)).await {
+        Ok(layout) => Json(ApiResponse::success(layout)),
+        Err(e) => {
+            error!("Layout task failed: {}", e);
+            Json(ApiResponse::error(format!("Layout task failed: {}", e)))
+        }
+    }
+}
+
 async fn save_project(
     State(graph_state): State<SharedGraphState>,
     Json(req): Json<SaveProjectRequest>,
 ) -> Json<ApiResponse<String>> {
     let project_data = ProjectData {
+        schema_version: PROJECT_SCHEMA_VERSION,
         name: req.name.clone(),
         nodes: req.nodes,
         edges: req.edges,
@@ -446,7 +1897,8 @@ async fn save_project(
             .to_string(),
     };
     
-    let state = graph_state.read().unwrap();
+    // Write lock serializes updates to the project index
+    let state = graph_state.write().unwrap();
     match state.save_project(&project_data) {
         Ok(()) => {
             info!("Project '{}' saved successfully", project_data.name);
@@ -459,6 +1911,57 @@ async fn save_project(
     }
 }
 
+async fn merge_project(
+    State(graph_state): State<SharedGraphState>,
+    Path(project_name): Path<String>,
+    Json(req): Json<MergeProjectRequest>,
+) -> Json<ApiResponse<MergeSummary>> {
+    // Write lock keeps the load-merge-save sequence atomic
+    let state = graph_state.write().unwrap();
+    let mut project = match state.load_project(&project_name) {
+        Ok(project) => project,
+        Err(e) => return Json(ApiResponse::error(e)),
+    };
+
+    let (nodes, edges) = match req.source {
+        Some(source) => {
+            if !req.nodes.is_empty() || !req.edges.is_empty() {
+                return Json(ApiResponse::error(
+                    "Provide either a source project or inline nodes/edges, not both".to_string(),
+                ));
+            }
+            if source == project_name {
+                return Json(ApiResponse::error(format!(
+                    "Cannot merge project '{}' into itself", project_name
+                )));
+            }
+            match state.load_project(&source) {
+                Ok(source_project) => (source_project.nodes, source_project.edges),
+                Err(e) => return Json(ApiResponse::error(e)),
+            }
+        }
+        None => (req.nodes, req.edges),
+    };
+
+    let summary = project.merge(nodes, edges, req.conflict_strategy);
+    project.timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        .to_string();
+
+    match state.save_project(&project) {
+        Ok(()) => {
+            info!("Merged into project '{}': {:?}", project_name, summary);
+            Json(ApiResponse::success(summary))
+        }
+        Err(e) => {
+            warn!("Failed to save merged project '{}': {}", project_name, e);
+            Json(ApiResponse::error(e))
+        }
+    }
+}
+
 async fn load_project(
     State(graph_state): State<SharedGraphState>,
     Path(project_name): Path<String>,
@@ -493,7 +1996,7 @@ async fn delete_project(
     State(graph_state): State<SharedGraphState>,
     Path(project_name): Path<String>,
 ) -> Json<ApiResponse<String>> {
-    let mut state = graph_state.write().unwrap();
+    let state = graph_state.write().unwrap();
     match state.delete_project(&project_name) {
         Ok(()) => {
             info!("Project '{}' deleted successfully", project_name);
@@ -519,21 +2022,58 @@ async fn main() {
     tracing_subscriber::fmt::init();
 
     let save_path = PathBuf::from("graph_data.json");
-    let graph_state = Arc::new(RwLock::new(GraphState::new(save_path)));
+    let mut state = GraphState::new(save_path);
+    state.event_capacity = match parse_event_capacity(std::env::var(EVENT_CAPACITY_ENV).ok().as_deref()) {
+        Ok(capacity) => capacity,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let ontology_path = std::env::var(EDGE_ONTOLOGY_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_EDGE_ONTOLOGY_PATH));
+    state.edge_ontology = match load_edge_ontology(&ontology_path) {
+        Ok(ontology) => ontology,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let graph_state = Arc::new(RwLock::new(state));
 
     let app = Router::new()
         .route("/", get(serve_ui))
         .route("/test", get(serve_test))
         .route("/api/graph", get(get_graph))
+        .route("/api/graph/mode", post(set_graph_mode))
+        .route("/api/graph/export", get(export_graph))
+        .route("/api/graph/components", get(get_components))
+        .route("/api/graph/subgraph", get(get_subgraph))
+        .route("/api/graph/stats", get(get_stats))
+        .route("/api/graph/cycles", get(get_cycles))
+        .route("/api/graph/layout", post(compute_and_store_layout))
         .route("/api/nodes", post(add_node))
         .route("/api/edges", post(add_edge))
+        .route("/api/nodes/batch", post(add_nodes_batch))
+        .route("/api/edges/batch", post(add_edges_batch))
         .route("/api/nodes/:id", delete(remove_node))
+        .route("/api/nodes/:id", patch(update_node))
         .route("/api/edges/:id", delete(remove_edge))
+        .route("/api/edges/:id", patch(update_edge))
         .route("/api/clear", post(clear_graph))
+        .route("/api/changes", get(get_changes))
+        .route("/api/ws", get(live_updates))
+        .route("/api/duplicates", get(get_duplicates))
+        .route("/api/dedupe", post(dedupe_nodes))
+        .route("/api/path", get(find_path))
+        .route("/api/path/:source/:target", get(find_path_between))
+        .route("/api/layout", get(compute_layout))
         .route("/api/projects", get(list_projects))
         .route("/api/projects", post(save_project))
         .route("/api/projects/:name", get(load_project))
         .route("/api/projects/:name", delete(delete_project))
+        .route("/api/projects/:name/merge", post(merge_project))
         .layer(CorsLayer::permissive())
         .with_state(graph_state);
 
@@ -557,18 +2097,42 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let save_path = temp_dir.path().join("test_graph.json");
         let graph_state = Arc::new(RwLock::new(GraphState::new(save_path)));
+        (test_router(graph_state), temp_dir)
+    }
 
-        let app = Router::new()
+    fn test_router(graph_state: SharedGraphState) -> Router {
+        Router::new()
             .route("/api/graph", get(get_graph))
+            .route("/api/graph/mode", post(set_graph_mode))
+            .route("/api/graph/export", get(export_graph))
+            .route("/api/graph/components", get(get_components))
+            .route("/api/graph/subgraph", get(get_subgraph))
+            .route("/api/graph/stats", get(get_stats))
+            .route("/api/graph/cycles", get(get_cycles))
+            .route("/api/graph/layout", post(compute_and_store_layout))
             .route("/api/nodes", post(add_node))
             .route("/api/edges", post(add_edge))
+            .route("/api/nodes/batch", post(add_nodes_batch))
+            .route("/api/edges/batch", post(add_edges_batch))
             .route("/api/nodes/:id", delete(remove_node))
+            .route("/api/nodes/:id", patch(update_node))
             .route("/api/edges/:id", delete(remove_edge))
+            .route("/api/edges/:id", patch(update_edge))
             .route("/api/clear", post(clear_graph))
+            .route("/api/path", get(find_path))
+            .route("/api/path/:source/:target", get(find_path_between))
+            .route("/api/layout", get(compute_layout))
+            .route("/api/changes", get(get_changes))
+            .route("/api/ws", get(live_updates))
+            .route("/api/duplicates", get(get_duplicates))
+            .route("/api/dedupe", post(dedupe_nodes))
+            .route("/api/projects", get(list_projects))
+            .route("/api/projects", post(save_project))
+            .route("/api/projects/:name", get(load_project))
+            .route("/api/projects/:name", delete(delete_project))
+            .route("/api/projects/:name/merge", post(merge_project))
             .layer(CorsLayer::permissive())
-            .with_state(graph_state);
-
-        (app, temp_dir)
+            .with_state(graph_state)
     }
 
     #[tokio::test]
@@ -908,4 +2472,1226 @@ mod tests {
         assert!(!result.success);
         assert!(result.error.unwrap().contains("Target node"));
     }
-}
\ No newline at end of file
+
+    async fn add_chain(server: &TestServer, ids: &[&str]) {
+        for id in ids {
+            server.post("/api/nodes").json(&json!({"id": id, "label": id})).await;
+        }
+        for pair in ids.windows(2) {
+            server.post("/api/edges").json(&json!({
+                "id": format!("{}-{}", pair[0], pair[1]),
+                "source": pair[0],
+                "target": pair[1]
+            })).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shortest_path_direct() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b"]).await;
+
+        let response = server.get("/api/path").add_query_params(json!({"from": "a", "to": "b"})).await;
+        response.assert_status_ok();
+
+        let result: ApiResponse<PathResult> = response.json();
+        assert!(result.success);
+        let path = result.data.unwrap();
+        assert_eq!(path.nodes, vec!["a", "b"]);
+        assert_eq!(path.edges, vec!["a-b"]);
+    }
+
+    #[tokio::test]
+    async fn test_shortest_path_multi_hop() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c", "d"]).await;
+
+        // Shortcut that makes the direct chain not the shortest route
+        server.post("/api/edges").json(&json!({"id": "a-c", "source": "a", "target": "c"})).await;
+
+        let response = server.get("/api/path").add_query_params(json!({"from": "a", "to": "d"})).await;
+        let result: ApiResponse<PathResult> = response.json();
+        assert!(result.success);
+        let path = result.data.unwrap();
+        assert_eq!(path.nodes, vec!["a", "c", "d"]);
+        assert_eq!(path.edges, vec!["a-c", "c-d"]);
+
+        // Edges are directed, so there is no way back
+        let response = server.get("/api/path").add_query_params(json!({"from": "d", "to": "a"})).await;
+        let result: ApiResponse<PathResult> = response.json();
+        assert!(result.success);
+        assert!(result.data.unwrap().nodes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shortest_path_disconnected() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b"]).await;
+        add_chain(&server, &["x", "y"]).await;
+
+        let response = server.get("/api/path").add_query_params(json!({"from": "a", "to": "y"})).await;
+        let result: ApiResponse<PathResult> = response.json();
+        assert!(result.success);
+        let path = result.data.unwrap();
+        assert!(path.nodes.is_empty());
+        assert!(path.edges.is_empty());
+
+        let response = server.get("/api/path").add_query_params(json!({"from": "a", "to": "missing"})).await;
+        let result: ApiResponse<PathResult> = response.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_circular_layout_places_nodes_on_circle() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c", "d", "e", "f"]).await;
+
+        let response = server.get("/api/layout").add_query_params(json!({"algo": "circular"})).await;
+        response.assert_status_ok();
+
+        let result: ApiResponse<HashMap<String, (f64, f64)>> = response.json();
+        assert!(result.success);
+        let positions = result.data.unwrap();
+        assert_eq!(positions.len(), 6);
+
+        for (x, y) in positions.values() {
+            let radius = (x * x + y * y).sqrt();
+            assert!((radius - LAYOUT_RADIUS).abs() < 1e-9, "Node should lie on the circle");
+        }
+
+        // Nodes are evenly spaced, so no two share a position
+        let distinct: HashSet<_> = positions.values()
+            .map(|(x, y)| ((x * 1000.0).round() as i64, (y * 1000.0).round() as i64))
+            .collect();
+        assert_eq!(distinct.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_force_layout_is_deterministic() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c", "d"]).await;
+        server.post("/api/nodes").json(&json!({"id": "loner", "label": "Loner"})).await;
+
+        let first: ApiResponse<HashMap<String, (f64, f64)>> = server.get("/api/layout")
+            .add_query_params(json!({"algo": "force"})).await.json();
+        let second: ApiResponse<HashMap<String, (f64, f64)>> = server.get("/api/layout")
+            .add_query_params(json!({"algo": "force"})).await.json();
+
+        let first = first.data.unwrap();
+        assert_eq!(first.len(), 5);
+        assert_eq!(first, second.data.unwrap());
+
+        for (x, y) in first.values() {
+            assert!(x.is_finite() && y.is_finite());
+            assert!(x.abs() <= LAYOUT_RADIUS && y.abs() <= LAYOUT_RADIUS);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes_since_sequence() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b"]).await;
+
+        // Two nodes and one edge so far
+        let result: ApiResponse<ChangesResponse> = server.get("/api/changes").await.json();
+        let changes = result.data.unwrap();
+        assert!(!changes.reset_required);
+        assert_eq!(changes.sequence, 3);
+        assert_eq!(changes.events.len(), 3);
+
+        server.delete("/api/nodes/a").await;
+        server.post("/api/clear").await;
+
+        let result: ApiResponse<ChangesResponse> = server.get("/api/changes")
+            .add_query_params(json!({"since": 3})).await.json();
+        let changes = result.data.unwrap();
+        assert!(!changes.reset_required);
+        assert_eq!(changes.sequence, 6);
+        let sequences: Vec<u64> = changes.events.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![4, 5, 6]);
+        // The node's edges are reported gone before the node itself
+        assert!(matches!(&changes.events[0].event, GraphEvent::EdgeRemoved { id } if id == "a-b"));
+        assert!(matches!(&changes.events[1].event, GraphEvent::NodeRemoved { id } if id == "a"));
+        assert!(matches!(changes.events[2].event, GraphEvent::Cleared));
+
+        // Up to date: nothing new
+        let result: ApiResponse<ChangesResponse> = server.get("/api/changes")
+            .add_query_params(json!({"since": 6})).await.json();
+        let changes = result.data.unwrap();
+        assert!(!changes.reset_required);
+        assert!(changes.events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_changes_beyond_buffer_require_reset() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut state = GraphState::new(temp_dir.path().join("test_graph.json"));
+        state.event_capacity = 2;
+        let server = TestServer::new(test_router(Arc::new(RwLock::new(state)))).unwrap();
+
+        for i in 1..=4 {
+            server.post("/api/nodes").json(&json!({"id": format!("n{}", i), "label": "n"})).await;
+        }
+
+        // Events 1 and 2 have been evicted
+        let result: ApiResponse<ChangesResponse> = server.get("/api/changes")
+            .add_query_params(json!({"since": 1})).await.json();
+        let changes = result.data.unwrap();
+        assert!(changes.reset_required);
+        assert!(changes.events.is_empty());
+        assert_eq!(changes.sequence, 4);
+
+        // Event 3 onwards is still buffered
+        let result: ApiResponse<ChangesResponse> = server.get("/api/changes")
+            .add_query_params(json!({"since": 2})).await.json();
+        let changes = result.data.unwrap();
+        assert!(!changes.reset_required);
+        assert_eq!(changes.events.len(), 2);
+
+        // A sequence from the future (e.g. before a server restart) also resets
+        let result: ApiResponse<ChangesResponse> = server.get("/api/changes")
+            .add_query_params(json!({"since": 99})).await.json();
+        assert!(result.data.unwrap().reset_required);
+    }
+
+    #[test]
+    fn test_event_capacity_from_env_value() {
+        assert_eq!(parse_event_capacity(None), Ok(DEFAULT_EVENT_CAPACITY));
+        assert_eq!(parse_event_capacity(Some("5000")), Ok(5000));
+        assert!(parse_event_capacity(Some("0")).is_err());
+        assert!(parse_event_capacity(Some("lots")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_layout_is_a_single_change_event() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+
+        let layout: ApiResponse<HashMap<String, (f64, f64)>> = server.post("/api/graph/layout")
+            .json(&json!({})).await.json();
+        let result: ApiResponse<ChangesResponse> = server.get("/api/changes")
+            .add_query_params(json!({"since": 5})).await.json();
+        let changes = result.data.unwrap();
+        assert_eq!(changes.events.len(), 1);
+        match &changes.events[0].event {
+            GraphEvent::LayoutComputed { positions } => assert_eq!(positions, &layout.data.unwrap()),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_duplicate_labels() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        for (id, label) in [("a1", "Auth"), ("a2", "Auth"), ("a3", "Auth"), ("b1", "Billing"), ("b2", "Billing"), ("c", "Cache")] {
+            server.post("/api/nodes").json(&json!({"id": id, "label": label})).await;
+        }
+
+        let response = server.get("/api/duplicates").await;
+        response.assert_status_ok();
+
+        let result: ApiResponse<Vec<Vec<String>>> = response.json();
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), vec![vec!["a1", "a2", "a3"], vec!["b1", "b2"]]);
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_repoints_edges_without_self_loops() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        for (id, label) in [("a1", "Auth"), ("a2", "Auth"), ("db", "Database"), ("ui", "Frontend")] {
+            server.post("/api/nodes").json(&json!({"id": id, "label": label})).await;
+        }
+        for (id, source, target) in [("e1", "a2", "db"), ("e2", "ui", "a2"), ("e3", "a1", "a2"), ("e4", "ui", "db")] {
+            server.post("/api/edges").json(&json!({"id": id, "source": source, "target": target})).await;
+        }
+
+        let response = server.post("/api/dedupe").await;
+        let result: ApiResponse<Vec<Vec<String>>> = response.json();
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), vec![vec!["a1", "a2"]]);
+
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        let data = graph.data.unwrap();
+        assert_eq!(data.nodes.len(), 3);
+        assert!(!data.nodes.contains_key("a2"));
+
+        // e3 (a1 -> a2) would have become a self-loop and is dropped
+        assert_eq!(data.edges.len(), 3);
+        assert!(!data.edges.contains_key("e3"));
+        assert_eq!(data.edges["e1"].source, "a1");
+        assert_eq!(data.edges["e2"].target, "a1");
+        assert_eq!(data.edges["e4"].source, "ui");
+        assert!(data.edges.values().all(|e| e.source != e.target));
+
+        let duplicates: ApiResponse<Vec<Vec<String>>> = server.get("/api/duplicates").await.json();
+        assert!(duplicates.data.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_merges_node_fields_into_survivor() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        server.post("/api/nodes").json(&json!({
+            "id": "a1", "label": "Auth", "metadata": {"owner": "alice", "tier": 1}
+        })).await;
+        server.post("/api/nodes").json(&json!({
+            "id": "a2", "label": "Auth", "color": "#ff6b6b", "size": 12.0,
+            "metadata": {"owner": "bob", "repo": "auth-svc"}
+        })).await;
+
+        server.post("/api/dedupe").await;
+
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        let survivor = &graph.data.unwrap().nodes["a1"];
+        // Survivor wins on conflicting keys; everything else is carried over
+        assert_eq!(survivor.metadata["owner"], json!("alice"));
+        assert_eq!(survivor.metadata["tier"], json!(1));
+        assert_eq!(survivor.metadata["repo"], json!("auth-svc"));
+        assert_eq!(survivor.color.as_deref(), Some("#ff6b6b"));
+        assert_eq!(survivor.size, Some(12.0));
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_collapses_parallel_edges() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        for (id, label) in [("a1", "Auth"), ("a2", "Auth"), ("db", "Database")] {
+            server.post("/api/nodes").json(&json!({"id": id, "label": label})).await;
+        }
+        for (id, source, target, label) in [
+            ("e1", "a1", "db", "reads"),
+            ("e2", "a2", "db", "reads"),
+            ("e3", "a2", "db", "writes"),
+        ] {
+            server.post("/api/edges").json(&json!({"id": id, "source": source, "target": target, "label": label})).await;
+        }
+
+        server.post("/api/dedupe").await;
+
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        let data = graph.data.unwrap();
+        // e2 would duplicate e1 and is dropped; e3 has a different label and stays
+        let mut ids: Vec<&String> = data.edges.keys().collect();
+        ids.sort();
+        assert_eq!(ids, vec!["e1", "e3"]);
+        assert_eq!(data.edges["e3"].source, "a1");
+    }
+
+    fn create_ontology_app(labels: &[&str]) -> (Router, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let ontology_path = temp_dir.path().join("edge_ontology.json");
+        fs::write(&ontology_path, json!(labels).to_string()).unwrap();
+
+        let mut state = GraphState::new(temp_dir.path().join("test_graph.json"));
+        state.edge_ontology = load_edge_ontology(&ontology_path).unwrap();
+        (test_router(Arc::new(RwLock::new(state))), temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_ontology_accepts_known_label() {
+        let (app, _temp_dir) = create_ontology_app(&["depends_on", "implements"]);
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b"]).await;
+
+        let edge_data = json!({"source": "a", "target": "b", "label": "depends_on"});
+        let result: ApiResponse<Edge> = server.post("/api/edges").json(&edge_data).await.json();
+        assert!(result.success);
+
+        // Unlabeled edges are not constrained by the ontology
+        let edge_data = json!({"source": "b", "target": "a"});
+        let result: ApiResponse<Edge> = server.post("/api/edges").json(&edge_data).await.json();
+        assert!(result.success);
+    }
+
+    #[tokio::test]
+    async fn test_ontology_rejects_unknown_label() {
+        let (app, _temp_dir) = create_ontology_app(&["implements", "depends_on"]);
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b"]).await;
+
+        let edge_data = json!({"id": "drifted", "source": "a", "target": "b", "label": "dependsOn"});
+        let result: ApiResponse<Edge> = server.post("/api/edges").json(&edge_data).await.json();
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.contains("dependsOn"));
+        assert!(error.contains("depends_on, implements"));
+
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        assert!(!graph.data.unwrap().edges.contains_key("drifted"));
+    }
+
+    #[tokio::test]
+    async fn test_ontology_disabled_allows_any_label() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(load_edge_ontology(&temp_dir.path().join("missing.json")), Ok(None));
+
+        // A file that exists but does not parse is an error, not "no ontology"
+        let broken = temp_dir.path().join("edge_ontology.json");
+        fs::write(&broken, "[\"depends_on\",]").unwrap();
+        assert!(load_edge_ontology(&broken).is_err());
+
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b"]).await;
+
+        let edge_data = json!({"source": "a", "target": "b", "label": "anything-goes"});
+        let result: ApiResponse<Edge> = server.post("/api/edges").json(&edge_data).await.json();
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_graph_without_mode_field_loads_as_directed() {
+        let legacy = json!({"nodes": {}, "edges": {}}).to_string();
+        let graph: Graph = serde_json::from_str(&legacy).unwrap();
+        assert!(graph.directed);
+    }
+
+    #[tokio::test]
+    async fn test_undirected_mode_traverses_both_ways() {
+        let temp_dir = TempDir::new().unwrap();
+        let save_path = temp_dir.path().join("test_graph.json");
+        let server = TestServer::new(test_router(Arc::new(RwLock::new(GraphState::new(save_path.clone()))))).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+
+        let result: ApiResponse<PathResult> = server.get("/api/path")
+            .add_query_params(json!({"from": "c", "to": "a"})).await.json();
+        assert!(result.data.unwrap().nodes.is_empty());
+
+        let result: ApiResponse<bool> = server.post("/api/graph/mode").json(&json!({"directed": false})).await.json();
+        assert_eq!(result.data, Some(false));
+
+        let result: ApiResponse<PathResult> = server.get("/api/path")
+            .add_query_params(json!({"from": "c", "to": "a"})).await.json();
+        let path = result.data.unwrap();
+        assert_eq!(path.nodes, vec!["c", "b", "a"]);
+        assert_eq!(path.edges, vec!["b-c", "a-b"]);
+
+        // Mode is persisted with the graph
+        let reloaded = Graph::load_from_file(&save_path);
+        assert!(!reloaded.directed);
+
+        // Removing a node cascades to edges on either side of it
+        server.delete("/api/nodes/b").await;
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        assert!(graph.data.unwrap().edges.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_path_route_between_nodes() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+        server.post("/api/nodes").json(&json!({"id": "island", "label": "Island"})).await;
+
+        let result: ApiResponse<Vec<String>> = server.get("/api/path/a/c").await.json();
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), vec!["a", "b", "c"]);
+
+        let result: ApiResponse<Vec<String>> = server.get("/api/path/c/a").await.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("No path"));
+
+        let result: ApiResponse<Vec<String>> = server.get("/api/path/a/island").await.json();
+        assert!(!result.success);
+
+        let result: ApiResponse<Vec<String>> = server.get("/api/path/a/ghost").await.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("'ghost' does not exist"));
+
+        // Undirected mode opens up the reverse route
+        server.post("/api/graph/mode").json(&json!({"directed": false})).await;
+        let result: ApiResponse<Vec<String>> = server.get("/api/path/c/a").await.json();
+        assert_eq!(result.data.unwrap(), vec!["c", "b", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_patch_node_preserves_other_fields_and_edges() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        server.post("/api/nodes").json(&json!({
+            "id": "n1",
+            "label": "Old Label",
+            "color": "#ff6b6b",
+            "size": 30.0,
+            "metadata": {"module": "auth"}
+        })).await;
+        server.post("/api/nodes").json(&json!({"id": "n2", "label": "Other"})).await;
+        server.post("/api/edges").json(&json!({"id": "e1", "source": "n1", "target": "n2"})).await;
+
+        let response = server.patch("/api/nodes/n1").json(&json!({"label": "New Label"})).await;
+        response.assert_status_ok();
+        let result: ApiResponse<Node> = response.json();
+        assert!(result.success);
+
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        let data = graph.data.unwrap();
+        let node = &data.nodes["n1"];
+        assert_eq!(node.label, "New Label");
+        assert_eq!(node.color, Some("#ff6b6b".to_string()));
+        assert_eq!(node.size, Some(30.0));
+        assert_eq!(node.metadata["module"], "auth");
+        assert!(data.edges.contains_key("e1"));
+
+        let result: ApiResponse<Node> = server.patch("/api/nodes/ghost").json(&json!({"label": "x"})).await.json();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn test_patch_edge_revalidates_endpoints() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+
+        let result: ApiResponse<Edge> = server.patch("/api/edges/a-b")
+            .json(&json!({"target": "c", "weight": 0.5})).await.json();
+        assert!(result.success);
+        let edge = result.data.unwrap();
+        assert_eq!(edge.source, "a");
+        assert_eq!(edge.target, "c");
+        assert_eq!(edge.weight, Some(0.5));
+
+        let result: ApiResponse<Edge> = server.patch("/api/edges/a-b")
+            .json(&json!({"source": "ghost", "label": "broken"})).await.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Source node"));
+
+        // A rejected patch leaves the edge untouched
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        let edge = &graph.data.unwrap().edges["a-b"];
+        assert_eq!(edge.source, "a");
+        assert_eq!(edge.label, None);
+    }
+
+    #[tokio::test]
+    async fn test_batch_create_nodes_and_edges() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        let response = server.post("/api/nodes/batch").json(&json!([
+            {"id": "a", "label": "A"},
+            {"label": "B"},
+            {"id": "c", "label": "C", "color": "#4ecdc4"}
+        ])).await;
+        response.assert_status_ok();
+        let result: ApiResponse<Vec<Node>> = response.json();
+        assert!(result.success);
+        let nodes = result.data.unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0].id, "a");
+        assert!(!nodes[1].id.is_empty());
+        let b_id = nodes[1].id.clone();
+
+        let result: ApiResponse<Vec<Edge>> = server.post("/api/edges/batch").json(&json!([
+            {"source": "a", "target": b_id},
+            {"id": "b-c", "source": b_id, "target": "c"}
+        ])).await.json();
+        assert!(result.success);
+        assert_eq!(result.data.unwrap()[1].id, "b-c");
+
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        let data = graph.data.unwrap();
+        assert_eq!(data.nodes.len(), 3);
+        assert_eq!(data.edges.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_is_rejected_atomically() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        server.post("/api/nodes").json(&json!({"id": "existing", "label": "Existing"})).await;
+
+        // Second item collides with an existing id
+        let result: ApiResponse<Vec<Node>> = server.post("/api/nodes/batch").json(&json!([
+            {"id": "fresh", "label": "Fresh"},
+            {"id": "existing", "label": "Clash"}
+        ])).await.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("Item 1"));
+
+        // Ids colliding within the batch itself are rejected too
+        let result: ApiResponse<Vec<Node>> = server.post("/api/nodes/batch").json(&json!([
+            {"id": "twin", "label": "Twin"},
+            {"id": "twin", "label": "Twin"}
+        ])).await.json();
+        assert!(!result.success);
+
+        let result: ApiResponse<Vec<Edge>> = server.post("/api/edges/batch").json(&json!([
+            {"id": "loop", "source": "existing", "target": "existing"},
+            {"id": "dangling", "source": "existing", "target": "missing"}
+        ])).await.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Target node"));
+
+        let graph: ApiResponse<Graph> = server.get("/api/graph").await.json();
+        let data = graph.data.unwrap();
+        assert_eq!(data.nodes.len(), 1);
+        assert!(data.edges.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_dot() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        server.post("/api/nodes").json(&json!({"id": "a", "label": "Say \"hi\"", "color": "#ff6b6b"})).await;
+        server.post("/api/nodes").json(&json!({"id": "b", "label": "C:\\path\nnext"})).await;
+        server.post("/api/edges").json(&json!({"id": "e1", "source": "a", "target": "b", "label": "uses", "weight": 0.5})).await;
+
+        let response = server.get("/api/graph/export").add_query_params(json!({"format": "dot"})).await;
+        response.assert_status_ok();
+        let dot = response.text();
+        assert_eq!(dot, concat!(
+            "digraph G {\n",
+            "    \"a\" [label=\"Say \\\"hi\\\"\", style=filled, fillcolor=\"#ff6b6b\"];\n",
+            "    \"b\" [label=\"C:\\\\path\\nnext\"];\n",
+            "    \"a\" -> \"b\" [label=\"uses\", influence=0.5];\n",
+            "}\n",
+        ));
+
+        server.post("/api/graph/mode").json(&json!({"directed": false})).await;
+        let dot = server.get("/api/graph/export").await.text();
+        assert!(dot.starts_with("graph G {"));
+        assert!(dot.contains("\"a\" -- \"b\""));
+
+        let response = server.get("/api/graph/export").add_query_params(json!({"format": "svg"})).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_connected_components() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+        add_chain(&server, &["x", "y"]).await;
+        server.post("/api/nodes").json(&json!({"id": "orphan", "label": "Orphan"})).await;
+        // Edge pointing "backwards" still joins the cluster
+        server.post("/api/nodes").json(&json!({"id": "d", "label": "D"})).await;
+        server.post("/api/edges").json(&json!({"source": "d", "target": "a"})).await;
+
+        let response = server.get("/api/graph/components").await;
+        response.assert_status_ok();
+        let result: ApiResponse<Vec<Vec<String>>> = response.json();
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), vec![
+            vec!["a", "b", "c", "d"],
+            vec!["x", "y"],
+            vec!["orphan"],
+        ]);
+
+        server.post("/api/graph/mode").json(&json!({"directed": false})).await;
+        let result: ApiResponse<Vec<Vec<String>>> = server.get("/api/graph/components").await.json();
+        assert_eq!(result.data.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_find_cycles() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+        add_chain(&server, &["x", "y"]).await;
+        server.post("/api/edges").json(&json!({"id": "a-c", "source": "a", "target": "c"})).await;
+
+        let result: ApiResponse<Vec<Vec<String>>> = server.get("/api/graph/cycles").await.json();
+        assert!(result.success);
+        assert!(result.data.unwrap().is_empty(), "A DAG has no cycles");
+
+        server.post("/api/edges").json(&json!({"id": "c-a", "source": "c", "target": "a"})).await;
+        server.post("/api/edges").json(&json!({"id": "y-x", "source": "y", "target": "x"})).await;
+        let result: ApiResponse<Vec<Vec<String>>> = server.get("/api/graph/cycles").await.json();
+        assert_eq!(result.data.unwrap(), vec![vec!["a", "b", "c"], vec!["x", "y"]]);
+    }
+
+    #[tokio::test]
+    async fn test_undirected_single_edge_is_not_a_cycle() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+        server.post("/api/graph/mode").json(&json!({"directed": false})).await;
+
+        let result: ApiResponse<Vec<Vec<String>>> = server.get("/api/graph/cycles").await.json();
+        assert!(result.data.unwrap().is_empty());
+
+        server.post("/api/edges").json(&json!({"id": "a-c", "source": "a", "target": "c"})).await;
+        let result: ApiResponse<Vec<Vec<String>>> = server.get("/api/graph/cycles").await.json();
+        assert_eq!(result.data.unwrap(), vec![vec!["a", "b", "c"]]);
+    }
+
+    #[test]
+    fn test_find_cycles_on_long_chain() {
+        // Deep enough to overflow a 2 MB stack with a recursive search
+        const LENGTH: usize = 50_000;
+        let ids: Vec<String> = (0..LENGTH).map(|i| format!("n{:05}", i)).collect();
+        let mut graph = Graph::new();
+        for id in &ids {
+            graph.add_node(Node {
+                id: id.clone(), label: id.clone(), color: None, size: None,
+                metadata: HashMap::new(), x: None, y: None,
+            }).unwrap();
+        }
+        let edge = |source: &str, target: &str| Edge {
+            id: format!("{}-{}", source, target), source: source.to_string(), target: target.to_string(),
+            label: None, weight: None, color: None, metadata: HashMap::new(),
+        };
+        for pair in ids.windows(2) {
+            graph.add_edge(edge(&pair[0], &pair[1])).unwrap();
+        }
+        assert!(graph.find_cycles().is_empty());
+
+        graph.add_edge(edge(&ids[LENGTH - 1], &ids[0])).unwrap();
+        let cycles = graph.find_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0], ids);
+    }
+
+    #[tokio::test]
+    async fn test_add_edge_reject_cycles() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+
+        let result: ApiResponse<Edge> = server.post("/api/edges")
+            .json(&json!({"source": "c", "target": "a", "reject_cycles": true})).await.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("would create a cycle"));
+
+        let result: ApiResponse<Edge> = server.post("/api/edges")
+            .json(&json!({"source": "a", "target": "c", "reject_cycles": true})).await.json();
+        assert!(result.success);
+
+        // Without the flag the cycle is allowed
+        let result: ApiResponse<Edge> = server.post("/api/edges")
+            .json(&json!({"source": "c", "target": "a"})).await.json();
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_node_without_position_deserializes() {
+        let legacy = json!({"id": "n", "label": "N", "color": null, "size": null, "metadata": {}});
+        let node: Node = serde_json::from_value(legacy).unwrap();
+        assert_eq!(node.x, None);
+        assert_eq!(node.y, None);
+    }
+
+    #[tokio::test]
+    async fn test_compute_layout_persists_deterministic_positions() {
+        let temp_dir = TempDir::new().unwrap();
+        let save_path = temp_dir.path().join("test_graph.json");
+        let server = TestServer::new(test_router(Arc::new(RwLock::new(GraphState::new(save_path.clone()))))).unwrap();
+        add_chain(&server, &["a", "b", "c", "d"]).await;
+
+        let response = server.post("/api/graph/layout").json(&json!({"iterations": 50})).await;
+        response.assert_status_ok();
+        let first: ApiResponse<HashMap<String, (f64, f64)>> = response.json();
+        let first = first.data.unwrap();
+        assert_eq!(first.len(), 4);
+
+        // Positions are stored on the nodes and survive a reload
+        let reloaded = Graph::load_from_file(&save_path);
+        for (id, (x, y)) in &first {
+            assert_eq!(reloaded.nodes[id].x, Some(*x));
+            assert_eq!(reloaded.nodes[id].y, Some(*y));
+        }
+
+        // A second run starts from the same id-seeded placement, not the stored one
+        let second: ApiResponse<HashMap<String, (f64, f64)>> = server.post("/api/graph/layout")
+            .json(&json!({"iterations": 50})).await.json();
+        assert_eq!(first, second.data.unwrap());
+
+        let result: ApiResponse<HashMap<String, (f64, f64)>> = server.post("/api/graph/layout")
+            .json(&json!({"iterations": MAX_LAYOUT_ITERATIONS + 1})).await.json();
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_apply_layout_skips_removed_nodes() {
+        let mut graph = Graph::new();
+        graph.add_node(Node {
+            id: "kept".to_string(), label: "Kept".to_string(), color: None, size: None,
+            metadata: HashMap::new(), x: None, y: None,
+        }).unwrap();
+        let positions = HashMap::from([
+            ("kept".to_string(), (1.0, 2.0)),
+            ("removed".to_string(), (3.0, 4.0)),
+        ]);
+
+        assert_eq!(graph.apply_layout(&positions), vec!["kept"]);
+        assert_eq!((graph.nodes["kept"].x, graph.nodes["kept"].y), (Some(1.0), Some(2.0)));
+        assert!(!graph.nodes.contains_key("removed"));
+    }
+
+    #[tokio::test]
+    async fn test_force_layout_endpoints_agree() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+        add_chain(&server, &["a", "b", "c"]).await;
+        server.post("/api/nodes").json(&json!({"id": "loner", "label": "Loner"})).await;
+
+        let computed: ApiResponse<HashMap<String, (f64, f64)>> = server.get("/api/layout")
+            .add_query_params(json!({"algo": "force"})).await.json();
+        let stored: ApiResponse<HashMap<String, (f64, f64)>> = server.post("/api/graph/layout")
+            .json(&json!({})).await.json();
+        assert_eq!(computed.data.unwrap(), stored.data.unwrap());
+    }
+
+    #[test]
+    fn test_string_metadata_files_still_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("legacy_graph.json");
+        fs::write(&path, json!({
+            "nodes": {
+                "n1": {"id": "n1", "label": "N1", "color": null, "size": null, "metadata": {"priority": "3"}}
+            },
+            "edges": {}
+        }).to_string()).unwrap();
+
+        let graph = Graph::load_from_file(&path);
+        assert_eq!(graph.nodes["n1"].metadata["priority"], json!("3"));
+    }
+
+    #[tokio::test]
+    async fn test_typed_metadata_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let save_path = temp_dir.path().join("test_graph.json");
+        let server = TestServer::new(test_router(Arc::new(RwLock::new(GraphState::new(save_path.clone()))))).unwrap();
+
+        let metadata = json!({
+            "priority": 3,
+            "reviewed": true,
+            "owners": ["alice", "bob"],
+            "source": {"file": "src/auth.rs", "line": 42}
+        });
+        server.post("/api/nodes").json(&json!({"id": "n1", "label": "Auth", "metadata": metadata})).await;
+        server.post("/api/nodes").json(&json!({"id": "n2", "label": "Db"})).await;
+        server.post("/api/edges").json(&json!({"id": "e1", "source": "n1", "target": "n2", "metadata": {"weight_hint": 0.25}})).await;
+        server.patch("/api/nodes/n2").json(&json!({"metadata": {"tables": 12}})).await;
+
+        let reloaded = Graph::load_from_file(&save_path);
+        let node = &reloaded.nodes["n1"];
+        assert_eq!(node.metadata["priority"], json!(3));
+        assert_eq!(node.metadata["reviewed"], json!(true));
+        assert_eq!(node.metadata["owners"], json!(["alice", "bob"]));
+        assert_eq!(node.metadata["source"]["line"], json!(42));
+        assert_eq!(reloaded.nodes["n2"].metadata["tables"], json!(12));
+        assert_eq!(reloaded.edges["e1"].metadata["weight_hint"], json!(0.25));
+    }
+
+    #[tokio::test]
+    async fn test_mutations_are_broadcast_to_live_subscribers() {
+        let temp_dir = TempDir::new().unwrap();
+        let graph_state = Arc::new(RwLock::new(GraphState::new(temp_dir.path().join("test_graph.json"))));
+        let mut first = graph_state.read().unwrap().live_events.subscribe();
+        let mut second = graph_state.read().unwrap().live_events.subscribe();
+        let server = TestServer::new(test_router(graph_state)).unwrap();
+
+        add_chain(&server, &["a", "b"]).await;
+        server.delete("/api/edges/a-b").await;
+        server.post("/api/clear").await;
+
+        for receiver in [&mut first, &mut second] {
+            let mut kinds = Vec::new();
+            while let Ok(event) = receiver.try_recv() {
+                kinds.push(serde_json::to_value(&event).unwrap()["type"].as_str().unwrap().to_string());
+            }
+            assert_eq!(kinds, vec!["node_added", "node_added", "edge_added", "edge_removed", "cleared"]);
+        }
+
+        // Failed mutations are not broadcast
+        server.delete("/api/nodes/ghost").await;
+        assert!(first.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_live_updates_over_websocket() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut state = GraphState::new(temp_dir.path().join("test_graph.json"));
+        state.live_events = broadcast::channel(4).0;
+        let graph_state = Arc::new(RwLock::new(state));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(std::future::IntoFuture::into_future(axum::serve(listener, test_router(graph_state.clone()))));
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/api/ws", addr)).await.unwrap();
+        let server = TestServer::new(test_router(graph_state.clone())).unwrap();
+
+        async fn next_json<S>(socket: &mut S) -> serde_json::Value
+        where
+            S: futures_util::Stream<Item = Result<WsMessage, tokio_tungstenite::tungstenite::Error>> + Unpin,
+        {
+            match socket.next().await.unwrap().unwrap() {
+                WsMessage::Text(text) => serde_json::from_str(&text).unwrap(),
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+
+        server.post("/api/nodes").json(&json!({"id": "a", "label": "A"})).await;
+        let event = next_json(&mut socket).await;
+        assert_eq!(event["type"], json!("node_added"));
+        assert_eq!(event["node"]["id"], json!("a"));
+
+        // Overflow the 4-slot channel before the forwarder gets to run
+        {
+            let mut state = graph_state.write().unwrap();
+            for i in 0..10 {
+                state.record_event(GraphEvent::NodeRemoved { id: format!("n{}", i) });
+            }
+        }
+        assert_eq!(next_json(&mut socket).await, json!({"type": "reset_required"}));
+        // Delivery resumes with the newest buffered events
+        let event = next_json(&mut socket).await;
+        assert_eq!(event["type"], json!("node_removed"));
+        assert_eq!(event["id"], json!("n6"));
+
+        socket.send(WsMessage::Close(None)).await.unwrap();
+        while let Some(message) = socket.next().await {
+            if message.is_err() {
+                break;
+            }
+        }
+    }
+
+    fn create_project_app() -> (Router, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut state = GraphState::new(temp_dir.path().join("test_graph.json"));
+        state.projects_path = temp_dir.path().join("projects");
+        fs::create_dir_all(&state.projects_path).unwrap();
+        (test_router(Arc::new(RwLock::new(state))), temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_load_pre_versioning_project() {
+        let (app, temp_dir) = create_project_app();
+        let server = TestServer::new(app).unwrap();
+
+        // Written before schema_version existed, with no config or timestamp
+        let legacy = json!({
+            "name": "legacy",
+            "nodes": {
+                "n1": {"id": "n1", "label": "Old", "color": null, "size": null, "metadata": {"k": "v"}}
+            },
+            "edges": {}
+        });
+        fs::write(temp_dir.path().join("projects").join("legacy.json"), legacy.to_string()).unwrap();
+
+        let result: ApiResponse<ProjectData> = server.get("/api/projects/legacy").await.json();
+        assert!(result.success, "{:?}", result.error);
+        let project = result.data.unwrap();
+        assert_eq!(project.schema_version, PROJECT_SCHEMA_VERSION);
+        assert_eq!(project.name, "legacy");
+        assert_eq!(project.nodes["n1"].label, "Old");
+        assert!(project.config.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_saved_projects_carry_schema_version() {
+        let (app, temp_dir) = create_project_app();
+        let server = TestServer::new(app).unwrap();
+
+        let result: ApiResponse<String> = server.post("/api/projects")
+            .json(&json!({"name": "current", "nodes": {}, "edges": {}})).await.json();
+        assert!(result.success);
+
+        let projects_dir = temp_dir.path().join("projects");
+        let index: HashMap<String, String> = serde_json::from_str(
+            &fs::read_to_string(projects_dir.join(PROJECT_INDEX_FILE)).unwrap()
+        ).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(projects_dir.join(&index["current"])).unwrap()
+        ).unwrap();
+        assert_eq!(stored["schema_version"], json!(PROJECT_SCHEMA_VERSION));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_project_named_index_survives() {
+        let (app, temp_dir) = create_project_app();
+        let server = TestServer::new(app).unwrap();
+
+        // Written by the old naming scheme as `projects/index.json`
+        let legacy = json!({"name": "index", "nodes": {}, "edges": {}});
+        let legacy_path = temp_dir.path().join("projects").join("index.json");
+        fs::write(&legacy_path, legacy.to_string()).unwrap();
+
+        let result: ApiResponse<Vec<String>> = server.get("/api/projects").await.json();
+        assert_eq!(result.data.unwrap(), vec!["index"]);
+
+        let result: ApiResponse<String> = server.post("/api/projects")
+            .json(&json!({"name": "other", "nodes": {}, "edges": {}})).await.json();
+        assert!(result.success);
+
+        let result: ApiResponse<Vec<String>> = server.get("/api/projects").await.json();
+        assert_eq!(result.data.unwrap(), vec!["index", "other"]);
+        let result: ApiResponse<ProjectData> = server.get("/api/projects/index").await.json();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.data.unwrap().name, "index");
+        let stored: serde_json::Value = serde_json::from_str(&fs::read_to_string(&legacy_path).unwrap()).unwrap();
+        assert_eq!(stored["name"], json!("index"));
+    }
+
+    #[test]
+    fn test_migrate_rejects_future_and_malformed_records() {
+        let future = json!({"schema_version": PROJECT_SCHEMA_VERSION + 1, "name": "x"});
+        assert!(matches!(ProjectData::migrate(future), Err(MigrationError::UnsupportedVersion(_))));
+
+        assert!(matches!(ProjectData::migrate(json!([1, 2])), Err(MigrationError::Invalid(_))));
+        assert!(matches!(ProjectData::migrate(json!({"nodes": {}})), Err(MigrationError::Invalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_projects_with_same_slug_do_not_clobber() {
+        let (app, temp_dir) = create_project_app();
+        let server = TestServer::new(app).unwrap();
+
+        for (name, label) in [("my:proj", "Colon"), ("my/proj", "Slash")] {
+            let result: ApiResponse<String> = server.post("/api/projects").json(&json!({
+                "name": name,
+                "nodes": {"n": {"id": "n", "label": label, "color": null, "size": null, "metadata": {}}},
+                "edges": {}
+            })).await.json();
+            assert!(result.success);
+        }
+
+        let result: ApiResponse<Vec<String>> = server.get("/api/projects").await.json();
+        assert_eq!(result.data.unwrap(), vec!["my/proj", "my:proj"]);
+
+        // Two project files plus the index
+        assert_eq!(fs::read_dir(temp_dir.path().join("projects")).unwrap().count(), 3);
+
+        let colon: ApiResponse<ProjectData> = server.get("/api/projects/my:proj").await.json();
+        assert_eq!(colon.data.unwrap().nodes["n"].label, "Colon");
+        let slash: ApiResponse<ProjectData> = server.get(&format!("/api/projects/{}", "my%2Fproj")).await.json();
+        assert_eq!(slash.data.unwrap().nodes["n"].label, "Slash");
+
+        let result: ApiResponse<String> = server.delete("/api/projects/my:proj").await.json();
+        assert!(result.success);
+        let result: ApiResponse<Vec<String>> = server.get("/api/projects").await.json();
+        assert_eq!(result.data.unwrap(), vec!["my/proj"]);
+
+        // Lookups are exact: the slug alone does not resolve
+        let result: ApiResponse<ProjectData> = server.get("/api/projects/my_proj").await.json();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn test_subgraph_by_metadata() {
+        let (app, _temp_dir) = create_project_app();
+        let server = TestServer::new(app).unwrap();
+
+        server.post("/api/nodes/batch").json(&json!([
+            {"id": "login", "label": "Login", "metadata": {"module": "auth"}},
+            {"id": "tokens", "label": "Tokens", "metadata": {"module": "auth"}},
+            {"id": "invoices", "label": "Invoices", "metadata": {"module": "billing"}},
+            {"id": "untagged", "label": "Untagged", "metadata": {"tier": 2}}
+        ])).await;
+        server.post("/api/edges/batch").json(&json!([
+            {"id": "inner", "source": "login", "target": "tokens"},
+            {"id": "crossing", "source": "tokens", "target": "invoices"},
+            {"id": "outside", "source": "invoices", "target": "untagged"}
+        ])).await;
+
+        let response = server.get("/api/graph/subgraph")
+            .add_query_params(json!({"key": "module", "value": "auth"})).await;
+        response.assert_status_ok();
+        let result: ApiResponse<Graph> = response.json();
+        assert!(result.success);
+        let subgraph = result.data.unwrap();
+
+        let mut node_ids: Vec<&String> = subgraph.nodes.keys().collect();
+        node_ids.sort();
+        assert_eq!(node_ids, vec!["login", "tokens"]);
+        assert_eq!(subgraph.edges.len(), 1);
+        assert!(subgraph.edges.contains_key("inner"));
+        for edge in subgraph.edges.values() {
+            assert!(subgraph.nodes.contains_key(&edge.source));
+            assert!(subgraph.nodes.contains_key(&edge.target));
+        }
+
+        // The extracted graph can be saved back as its own project
+        let result: ApiResponse<String> = server.post("/api/projects").json(&json!({
+            "name": "auth-only",
+            "nodes": subgraph.nodes,
+            "edges": subgraph.edges
+        })).await.json();
+        assert!(result.success);
+        let project: ApiResponse<ProjectData> = server.get("/api/projects/auth-only").await.json();
+        assert_eq!(project.data.unwrap().nodes.len(), 2);
+
+        // Non-string metadata matches on its JSON value
+        let result: ApiResponse<Graph> = server.get("/api/graph/subgraph")
+            .add_query_params(json!({"key": "tier", "value": "2"})).await.json();
+        assert!(result.data.unwrap().nodes.contains_key("untagged"));
+
+        let result: ApiResponse<Graph> = server.get("/api/graph/subgraph")
+            .add_query_params(json!({"key": "module", "value": "nope"})).await.json();
+        let empty = result.data.unwrap();
+        assert!(empty.nodes.is_empty() && empty.edges.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_degree_stats() {
+        let (app, _temp_dir) = create_test_app();
+        let server = TestServer::new(app).unwrap();
+
+        // hub -> a, hub -> b, a -> b, plus an isolated node
+        for id in ["hub", "a", "b", "lonely"] {
+            server.post("/api/nodes").json(&json!({"id": id, "label": id.to_uppercase()})).await;
+        }
+        for (source, target) in [("hub", "a"), ("hub", "b"), ("a", "b")] {
+            server.post("/api/edges").json(&json!({"source": source, "target": target})).await;
+        }
+
+        let response = server.get("/api/graph/stats").await;
+        response.assert_status_ok();
+        let result: ApiResponse<Vec<NodeStats>> = response.json();
+        assert!(result.success);
+        let stats = result.data.unwrap();
+        assert_eq!(stats.len(), 4);
+
+        let by_id: HashMap<&str, &NodeStats> = stats.iter().map(|s| (s.id.as_str(), s)).collect();
+        assert_eq!((by_id["hub"].in_degree, by_id["hub"].out_degree), (0, 2));
+        assert_eq!((by_id["a"].in_degree, by_id["a"].out_degree), (1, 1));
+        assert_eq!((by_id["b"].in_degree, by_id["b"].out_degree), (2, 0));
+        assert_eq!(by_id["b"].label, "B");
+
+        let lonely = by_id["lonely"];
+        assert_eq!((lonely.in_degree, lonely.out_degree, lonely.centrality), (0, 0, 0.0));
+        assert_eq!(stats.last().unwrap().id, "lonely");
+        assert!((by_id["hub"].centrality - 2.0 / 6.0).abs() < 1e-9);
+
+        server.post("/api/graph/mode").json(&json!({"directed": false})).await;
+        let result: ApiResponse<Vec<NodeStats>> = server.get("/api/graph/stats").await.json();
+        let stats = result.data.unwrap();
+        let hub = stats.iter().find(|s| s.id == "hub").unwrap();
+        assert_eq!((hub.in_degree, hub.out_degree), (2, 2));
+        assert!((hub.centrality - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    async fn save_test_project(server: &TestServer, name: &str, nodes: serde_json::Value, edges: serde_json::Value) {
+        let result: ApiResponse<String> = server.post("/api/projects").json(&json!({
+            "name": name, "nodes": nodes, "edges": edges, "config": null
+        })).await.json();
+        assert!(result.success);
+    }
+
+    fn test_node(id: &str, label: &str) -> serde_json::Value {
+        json!({"id": id, "label": label, "color": null, "size": null, "metadata": {}})
+    }
+
+    fn test_edge(id: &str, source: &str, target: &str) -> serde_json::Value {
+        json!({"id": id, "source": source, "target": target, "label": null,
+               "weight": null, "color": null, "metadata": {}})
+    }
+
+    #[tokio::test]
+    async fn test_merge_projects_with_conflict_strategies() {
+        let (app, _temp_dir) = create_project_app();
+        let server = TestServer::new(app).unwrap();
+
+        let base_nodes = json!({"a": test_node("a", "Base A"), "b": test_node("b", "Base B")});
+        let base_edges = json!({"e1": test_edge("e1", "a", "b")});
+        let team_nodes = json!({"a": test_node("a", "Team A"), "c": test_node("c", "Team C")});
+        let team_edges = json!({
+            "e1": test_edge("e1", "c", "a"),
+            "e2": test_edge("e2", "a", "ghost"),
+        });
+        save_test_project(&server, "team", team_nodes, team_edges).await;
+
+        for (strategy, expected) in [
+            ("skip", MergeSummary { nodes_added: 1, nodes_skipped: 1, edges_skipped: 2, ..Default::default() }),
+            ("overwrite", MergeSummary { nodes_added: 1, nodes_overwritten: 1, edges_added: 1, edges_skipped: 1, ..Default::default() }),
+            ("rename", MergeSummary { nodes_added: 1, nodes_renamed: 1, edges_added: 1, edges_skipped: 1, ..Default::default() }),
+        ] {
+            save_test_project(&server, "base", base_nodes.clone(), base_edges.clone()).await;
+            let result: ApiResponse<MergeSummary> = server.post("/api/projects/base/merge")
+                .json(&json!({"source": "team", "conflict_strategy": strategy}))
+                .await.json();
+            assert!(result.success, "{}", strategy);
+            assert_eq!(result.data.unwrap(), expected, "{}", strategy);
+
+            let project = server.get("/api/projects/base").await.json::<ApiResponse<ProjectData>>().data.unwrap();
+            for edge in project.edges.values() {
+                assert!(project.nodes.contains_key(&edge.source) && project.nodes.contains_key(&edge.target));
+            }
+            match strategy {
+                "skip" => {
+                    assert_eq!(project.nodes["a"].label, "Base A");
+                    assert_eq!(project.edges["e1"].source, "a");
+                }
+                "overwrite" => {
+                    assert_eq!(project.nodes["a"].label, "Team A");
+                    assert_eq!((project.edges["e1"].source.as_str(), project.edges["e1"].target.as_str()), ("c", "a"));
+                }
+                _ => {
+                    assert_eq!(project.nodes["a"].label, "Base A");
+                    assert_eq!(project.nodes["a-2"].label, "Team A");
+                    // Colliding edge id is renamed and rewired to the renamed node
+                    assert_eq!(project.edges["e1"].source, "a");
+                    let rewired = &project.edges["e1-2"];
+                    assert_eq!((rewired.source.as_str(), rewired.target.as_str()), ("c", "a-2"));
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_merge_inline_nodes_and_validation() {
+        let (app, _temp_dir) = create_project_app();
+        let server = TestServer::new(app).unwrap();
+
+        // Target already holds a dangling edge; merging cleans it up
+        save_test_project(&server, "base", json!({"a": test_node("a", "A")}),
+            json!({"bad": test_edge("bad", "a", "missing")})).await;
+
+        let result: ApiResponse<MergeSummary> = server.post("/api/projects/base/merge")
+            .json(&json!({
+                "nodes": {"b": test_node("b", "B")},
+                "edges": {"ab": test_edge("ab", "a", "b")}
+            }))
+            .await.json();
+        let summary = result.data.unwrap();
+        assert_eq!((summary.nodes_added, summary.edges_added, summary.edges_dropped), (1, 1, 1));
+
+        let project = server.get("/api/projects/base").await.json::<ApiResponse<ProjectData>>().data.unwrap();
+        assert_eq!(project.nodes.len(), 2);
+        assert_eq!(project.edges.keys().collect::<Vec<_>>(), vec!["ab"]);
+
+        let result: ApiResponse<MergeSummary> = server.post("/api/projects/base/merge")
+            .json(&json!({"source": "base", "nodes": {"c": test_node("c", "C")}}))
+            .await.json();
+        assert!(!result.success);
+
+        // Self-merge would just duplicate everything under renamed ids
+        let result: ApiResponse<MergeSummary> = server.post("/api/projects/base/merge")
+            .json(&json!({"source": "base", "conflict_strategy": "rename"}))
+            .await.json();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("into itself"));
+        let project = server.get("/api/projects/base").await.json::<ApiResponse<ProjectData>>().data.unwrap();
+        assert_eq!(project.nodes.len(), 2);
+
+        let result: ApiResponse<MergeSummary> = server.post("/api/projects/nope/merge")
+            .json(&json!({"nodes": {}}))
+            .await.json();
+        assert!(!result.success);
+
+        let result: ApiResponse<MergeSummary> = server.post("/api/projects/base/merge")
+            .json(&json!({"source": "nope"}))
+            .await.json();
+        assert!(!result.success);
+    }
+}